@@ -1,13 +1,162 @@
-use std::sync::Arc;
+use std::hash::Hasher;
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use rayon::prelude::*;
-
-use crate::colour::{Colour, write_colour};
-use crate::hittable::{HitRecord, Hittable};
+use crate::checkpoint::{Checkpoint, CheckpointError, CheckpointSettings, Fingerprint};
+use crate::colour::{Colour, luminance, srgb_to_linear};
+use crate::framebuffer::Framebuffer;
+use crate::hittable::{HitRecord, Hittable, HittableList};
 use crate::interval::Interval;
+use crate::material::Material;
+use crate::progress::{Progress, StderrProgress};
 use crate::ray::Ray;
-use crate::vec3::{Point3, Vec3, random_in_unit_disk};
-use crate::{INFINITY, camera, degrees_to_radians, random_f64};
+use crate::rng::Pcg32;
+use crate::sampler::{Sampler, SamplerKind};
+use crate::tile::{self, TileOrder};
+use crate::vec3::{Point3, Vec3, sample_unit_disk};
+use crate::{DEFAULT_SEED, INFINITY, degrees_to_radians, seed_random};
+
+#[derive(Debug, Default, Clone, Copy)]
+pub enum Background {
+    #[default]
+    Sky, // Blue-white gradient along the ray's y direction
+    Solid(Colour),
+    Gradient {
+        bottom: Colour,
+        top: Colour,
+    },
+}
+
+impl Background {
+    pub fn colour(&self, ray: &Ray) -> Colour {
+        match *self {
+            Background::Sky => Background::Gradient {
+                bottom: Colour::new(1.0, 1.0, 1.0),
+                top: Colour::new(0.5, 0.7, 1.0),
+            }
+            .colour(ray),
+            Background::Solid(colour) => colour,
+            Background::Gradient { bottom, top } => {
+                let unit_direction = ray.direction.unit();
+                let a = 0.5 * (unit_direction.y + 1.0);
+                (1.0 - a) * bottom + a * top
+            }
+        }
+    }
+}
+
+// Per-pixel sample counts chosen from the variance of each pixel's samples. Pixels take
+// `min_samples` first, then `samples_per_round` more per round until the 95% confidence
+// interval of their luminance is within `max_error` of the estimate or they reach
+// `max_samples`.
+#[derive(Debug, Clone, Copy)]
+pub struct AdaptiveSampling {
+    pub min_samples: i32, // Samples every pixel takes before its error is checked
+    pub max_samples: i32, // Per-pixel cap
+    pub samples_per_round: i32, // Samples added to each unconverged pixel per round
+    pub max_error: f64,   // Allowed confidence interval half-width, relative to luminance
+}
+
+// Luminance below which error is measured in absolute rather than relative terms, so that
+// near-black pixels don't chase a vanishing target
+const MIN_ERROR_LUMINANCE: f64 = 0.01;
+
+impl AdaptiveSampling {
+    pub fn new(max_error: f64, max_samples: i32) -> Self {
+        Self {
+            min_samples: 16.min(max_samples),
+            max_samples,
+            samples_per_round: 16,
+            max_error,
+        }
+    }
+
+    fn converged(&self, pixel: &PixelStats) -> bool {
+        if pixel.samples < 2 {
+            return false;
+        }
+
+        let n = pixel.samples as f64;
+        let mean = pixel.luminance_sum / n;
+        let variance = ((pixel.luminance_sq_sum - n * mean * mean) / (n - 1.0)).max(0.0);
+        let half_width = 1.96 * (variance / n).sqrt();
+        half_width <= self.max_error * mean.max(MIN_ERROR_LUMINANCE)
+    }
+}
+
+// Settings for `Camera::render_progressive`
+#[derive(Debug, Clone)]
+pub struct Progressive {
+    pub samples_per_pass: i32,         // Samples added to every pixel per pass
+    pub time_budget: Option<Duration>, // Wall-clock limit; no new pass starts if it would overrun
+    pub checkpoint: Option<CheckpointSettings>, // Periodically save, and resume from, a checkpoint
+}
+
+impl Progressive {
+    pub fn new(samples_per_pass: i32) -> Self {
+        Self {
+            samples_per_pass: samples_per_pass.max(1),
+            time_budget: None,
+            checkpoint: None,
+        }
+    }
+
+    pub fn with_checkpoint(mut self, settings: CheckpointSettings) -> Self {
+        self.checkpoint = Some(settings);
+        self
+    }
+
+    pub fn with_time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+}
+
+// Progress reported after each progressive pass
+#[derive(Debug, Clone, Copy)]
+pub struct Pass {
+    pub index: usize,      // Passes completed, starting at 1
+    pub samples: i32,      // Samples per pixel accumulated so far
+    pub elapsed: Duration, // Time since the render started
+}
+
+fn write_vec3(h: &mut Fingerprint, v: Vec3) {
+    h.write_f64(v.x);
+    h.write_f64(v.y);
+    h.write_f64(v.z);
+}
+
+// Multiple importance sampling weight for a sample drawn with density `pdf` when `other` is
+// the density of the other strategy producing it
+fn power_heuristic(pdf: f64, other: f64) -> f64 {
+    let (a, b) = (pdf * pdf, other * other);
+    a / (a + b)
+}
+
+#[derive(Debug, Default, Clone)]
+struct PixelStats {
+    sum: Colour,
+    luminance_sum: f64,
+    luminance_sq_sum: f64,
+    samples: i32,
+    done: bool,
+}
+
+impl PixelStats {
+    fn add(&mut self, colour: Colour) {
+        let y = luminance(colour);
+        self.sum += colour;
+        self.luminance_sum += y;
+        self.luminance_sq_sum += y * y;
+        self.samples += 1;
+    }
+}
+
+// Mixed into the seed of the thread-local stream that participating media draw from during a
+// sample, so it never lines up with the sampler's own per-sample streams
+const MEDIUM_STREAM_KEY: u64 = 0x6d65_6469_756d_7321;
 
 #[derive(Debug, Default)]
 pub struct Camera {
@@ -15,6 +164,8 @@ pub struct Camera {
     pub image_width: i32,       // Rendered image width in pixels
     pub samples_per_pixel: i32, // Num of random samples per pixel (anti-aliasing)
     pub max_depth: i32,         // Max num of ray bounces
+    pub background: Background, // Scene colour for rays that hit nothing
+    pub light_sampling: bool,   // Sample lights directly at non-specular bounces
 
     pub vfov: f64,        // Vertical FOV (in degrees)
     pub lookfrom: Point3, // Point camera is looking from
@@ -24,6 +175,17 @@ pub struct Camera {
     pub defocus_angle: f64, // Variation angle of rays through each pixel
     pub focus_dist: f64,    // Camera lookfrom to plane of perfect focus
 
+    pub shutter_open: f64,  // Time the exposure starts
+    pub shutter_close: f64, // Time the exposure ends
+
+    pub seed: u64,            // Base seed for the per-pixel random streams
+    pub sampler: SamplerKind, // How sample values are spread over pixels, lens and bounces
+    pub adaptive: Option<AdaptiveSampling>, // Vary samples per pixel instead of taking a fixed count
+
+    pub tile_size: usize, // Width and height of the blocks rendered as a unit
+    pub tile_order: TileOrder, // Order tiles are rendered in
+    pub progress: Option<Arc<dyn Progress>>, // Receives finished tiles and passes
+
     image_height: i32,
     pixel_sample_scale: f64,
     centre: Point3,
@@ -36,6 +198,8 @@ pub struct Camera {
     w: Vec3,
     defocus_disk_u: Vec3,
     defocus_disk_v: Vec3,
+    sample_counts: Vec<u32>,
+    lights: HittableList, // Emitters in the world being rendered, for light sampling
 }
 
 impl Camera {
@@ -45,49 +209,380 @@ impl Camera {
             image_width: 100,
             samples_per_pixel: 10,
             max_depth: 10,
+            background: Background::Sky,
+            light_sampling: true,
             vfov: 90.0,
             lookfrom: Point3::zero(),
             lookat: Point3::new(0.0, 0.0, -1.0),
             vup: Vec3::new(0.0, 1.0, 0.0),
             defocus_angle: 0.0,
             focus_dist: 10.0,
+            shutter_open: 0.0,
+            shutter_close: 1.0,
+            seed: DEFAULT_SEED,
+            tile_size: 32,
+            tile_order: TileOrder::Spiral,
+            progress: Some(Arc::new(StderrProgress::new())),
             ..Default::default()
         }
     }
 
-    pub fn render(&mut self, world: Arc<dyn Hittable>) {
-        self.initialize();
+    pub fn render(&mut self, world: Arc<dyn Hittable>) -> Framebuffer {
+        let mut image = Framebuffer::default();
+        self.render_into(world, &mut image);
+        image
+    }
+
+    // Renders into a caller supplied framebuffer, resizing it to the image dimensions if needed
+    pub fn render_into(&mut self, world: Arc<dyn Hittable>, image: &mut Framebuffer) {
+        self.initialize(&world);
 
-        let image_size = self.image_width * self.image_height;
-        eprintln!("\rConstructing image with {} pixels", image_size);
-        let mut image = vec![Colour::zero(); image_size as usize];
-        (0..image_size)
-            .into_par_iter()
-            .map(|index| {
-                let i = index % self.image_width;
-                let j = index / self.image_width;
+        let (width, height) = (self.image_width as usize, self.image_height as usize);
+        if image.width() != width || image.height() != height {
+            image.resize(width, height);
+        }
 
-                if index % 1000 == 0 {
-                    eprintln!("\rRendered: {}/{}", index, image_size);
-                }
+        match self.adaptive {
+            None => {
+                let mut pixels = vec![Colour::zero(); width * height];
+                self.render_pass(
+                    1,
+                    &mut pixels,
+                    image,
+                    |index, pixel| {
+                        let mut pixel_colour = Colour::zero();
+                        self.sample_pixel(&world, index, 0..self.samples_per_pixel, |colour| {
+                            pixel_colour += colour
+                        });
+                        *pixel = self.pixel_sample_scale * pixel_colour;
+                    },
+                    |&pixel| pixel,
+                );
+                self.sample_counts = vec![self.samples_per_pixel as u32; width * height];
+            }
+            Some(adaptive) => self.render_adaptive(&world, image, &adaptive),
+        }
+    }
+
+    // Renders in passes of `samples_per_pass` samples per pixel until `samples_per_pixel` is
+    // reached or the time budget runs out, calling `on_pass` with the image so far after each
+    // pass. Adaptive sampling settings are ignored; every pixel gets the same samples.
+    //
+    // With checkpointing enabled the accumulated samples are saved periodically, and a render
+    // started with an existing checkpoint for the same scene and camera continues from it. If
+    // the checkpoint already has enough samples, `on_pass` is called once with its image.
+    pub fn render_progressive(
+        &mut self,
+        world: Arc<dyn Hittable>,
+        progressive: &Progressive,
+        mut on_pass: impl FnMut(&Pass, &Framebuffer),
+    ) -> Result<Framebuffer, CheckpointError> {
+        self.initialize(&world);
 
-                let mut pixel_colour = Colour::zero();
-                for _sample in 0..self.samples_per_pixel {
-                    let r = self.get_ray(i, j);
-                    pixel_colour += Camera::ray_colour(&r, self.max_depth, world.clone());
+        let (width, height) = (self.image_width as usize, self.image_height as usize);
+        let mut image = Framebuffer::new(width, height);
+        let mut pixels = vec![(Colour::zero(), 0); width * height];
+
+        let fingerprint = progressive
+            .checkpoint
+            .as_ref()
+            .map(|settings| self.fingerprint(settings.scene_fingerprint));
+        if let Some(settings) = &progressive.checkpoint
+            && settings.path.exists()
+        {
+            let checkpoint = Checkpoint::load(&settings.path)?;
+            if Some(checkpoint.fingerprint) != fingerprint
+                || checkpoint.width != width
+                || checkpoint.height != height
+            {
+                return Err(CheckpointError::Mismatch {
+                    path: settings.path.clone(),
+                });
+            }
+            pixels = checkpoint
+                .sums
+                .into_iter()
+                .zip(checkpoint.samples)
+                .map(|(sum, n)| (sum, n as i32))
+                .collect();
+            // Start from the saved estimate, it is the whole result if no pass is needed
+            for (value, &(sum, count)) in image.pixels_mut().iter_mut().zip(&pixels) {
+                if count > 0 {
+                    *value = sum / count as f64;
                 }
-                pixel_colour
+            }
+        }
+
+        let start = Instant::now();
+        let mut last_save = start;
+        let min_samples = |pixels: &[(Colour, i32)]| pixels.iter().map(|p| p.1).min().unwrap_or(0);
+        let mut samples = min_samples(&pixels);
+        let mut pass = 0;
+        // The field is public, so a struct literal can skip the clamp in Progressive::new
+        let samples_per_pass = progressive.samples_per_pass.max(1);
+
+        while samples < self.samples_per_pixel {
+            let pass_start = Instant::now();
+            pass += 1;
+            self.render_pass(
+                pass,
+                &mut pixels,
+                &mut image,
+                |index, (sum, count)| {
+                    let end = (*count + samples_per_pass).min(self.samples_per_pixel);
+                    self.sample_pixel(&world, index, *count..end, |colour| *sum += colour);
+                    *count = end.max(*count);
+                },
+                |&(sum, count)| sum / count as f64,
+            );
+            samples = min_samples(&pixels);
+
+            let info = Pass {
+                index: pass,
+                samples,
+                elapsed: start.elapsed(),
+            };
+            on_pass(&info, &image);
+
+            // Stop early rather than start a pass that would overrun the budget
+            let out_of_time = progressive
+                .time_budget
+                .is_some_and(|budget| info.elapsed + pass_start.elapsed() > budget);
+            let finished = out_of_time || samples >= self.samples_per_pixel;
+
+            if let (Some(settings), Some(fingerprint)) = (&progressive.checkpoint, fingerprint)
+                && (finished || last_save.elapsed() >= settings.interval)
+            {
+                let checkpoint = Checkpoint {
+                    fingerprint,
+                    width,
+                    height,
+                    samples: pixels.iter().map(|p| p.1 as u32).collect(),
+                    sums: pixels.iter().map(|p| p.0).collect(),
+                };
+                checkpoint.save(&settings.path)?;
+                last_save = Instant::now();
+            }
+
+            if out_of_time {
+                break;
+            }
+        }
+        // A checkpoint that already has enough samples still reports its image once
+        if pass == 0 {
+            let info = Pass {
+                index: 0,
+                samples,
+                elapsed: start.elapsed(),
+            };
+            on_pass(&info, &image);
+        }
+        self.sample_counts = pixels.iter().map(|p| p.1 as u32).collect();
+        Ok(image)
+    }
+
+    // Identifies everything about this camera that affects the samples it produces, combined
+    // with a caller supplied fingerprint of the scene. The sample count is left out so a
+    // resumed render can raise it, except with the stratified sampler whose strata depend on it.
+    pub fn fingerprint(&self, scene: u64) -> u64 {
+        let mut h = Fingerprint::new();
+        h.write_u64(scene);
+        h.write_f64(self.aspect_ratio);
+        h.write_u32(self.image_width as u32);
+        h.write_u32(self.max_depth as u32);
+        match self.background {
+            Background::Sky => h.write_u32(0),
+            Background::Solid(colour) => {
+                h.write_u32(1);
+                write_vec3(&mut h, colour);
+            }
+            Background::Gradient { bottom, top } => {
+                h.write_u32(2);
+                write_vec3(&mut h, bottom);
+                write_vec3(&mut h, top);
+            }
+        }
+        h.write_f64(self.vfov);
+        for v in [self.lookfrom, self.lookat, self.vup] {
+            write_vec3(&mut h, v);
+        }
+        h.write_f64(self.defocus_angle);
+        h.write_f64(self.focus_dist);
+        h.write_f64(self.shutter_open);
+        h.write_f64(self.shutter_close);
+        h.write_u64(self.seed);
+        h.write_u32(self.sampler as u32);
+        h.write_u8(self.light_sampling as u8);
+        if self.sampler == SamplerKind::Stratified {
+            h.write_u32(self.samples_per_pixel as u32);
+        }
+        h.finish()
+    }
+
+    // Samples taken by each pixel in the last render, in row-major order
+    pub fn sample_counts(&self) -> &[u32] {
+        &self.sample_counts
+    }
+
+    // False-colour image of the last render's sample counts, from black (fewest) through red
+    // and yellow to white (the per-pixel maximum)
+    pub fn sample_heatmap(&self) -> Framebuffer {
+        const RAMP: [(f64, f64, f64); 5] = [
+            (0.0, 0.0, 0.0),
+            (0.3, 0.0, 0.5),
+            (0.9, 0.2, 0.1),
+            (1.0, 0.8, 0.0),
+            (1.0, 1.0, 1.0),
+        ];
+
+        let max_samples = match self.adaptive {
+            Some(adaptive) => adaptive.max_samples,
+            None => self.samples_per_pixel,
+        };
+        let pixels = self
+            .sample_counts
+            .iter()
+            .map(|&count| {
+                let t =
+                    (count as f64 / max_samples as f64).clamp(0.0, 1.0) * (RAMP.len() - 1) as f64;
+                let k = (t as usize).min(RAMP.len() - 2);
+                let f = t - k as f64;
+                let (a, b) = (RAMP[k], RAMP[k + 1]);
+                Colour::new(
+                    srgb_to_linear(a.0 + f * (b.0 - a.0)),
+                    srgb_to_linear(a.1 + f * (b.1 - a.1)),
+                    srgb_to_linear(a.2 + f * (b.2 - a.2)),
+                )
             })
-            .collect_into_vec(&mut image);
+            .collect();
+        Framebuffer::from_pixels(
+            self.image_width as usize,
+            self.image_height as usize,
+            pixels,
+        )
+    }
+
+    // Renders in rounds, adding samples only to pixels whose estimate isn't yet within the
+    // error target
+    fn render_adaptive(
+        &mut self,
+        world: &Arc<dyn Hittable>,
+        image: &mut Framebuffer,
+        adaptive: &AdaptiveSampling,
+    ) {
+        // The settings are public fields, so make sure every pixel can make progress and stop
+        let adaptive = AdaptiveSampling {
+            min_samples: adaptive.min_samples.max(2),
+            max_samples: adaptive.max_samples.max(1),
+            samples_per_round: adaptive.samples_per_round.max(1),
+            ..*adaptive
+        };
+        let mut stats = vec![PixelStats::default(); image.pixels().len()];
+        let mut round = 0;
+        while stats.iter().any(|pixel| !pixel.done) {
+            round += 1;
+            self.render_pass(
+                round,
+                &mut stats,
+                image,
+                |index, pixel| {
+                    if pixel.done {
+                        return;
+                    }
+
+                    let target = if pixel.samples == 0 {
+                        adaptive.min_samples
+                    } else {
+                        pixel.samples + adaptive.samples_per_round
+                    };
+                    let samples = pixel.samples..target.min(adaptive.max_samples);
+                    self.sample_pixel(world, index, samples, |colour| pixel.add(colour));
+                    pixel.done = pixel.samples >= adaptive.max_samples || adaptive.converged(pixel);
+                },
+                |pixel| pixel.sum / pixel.samples as f64,
+            );
+        }
+        self.sample_counts = stats.iter().map(|pixel| pixel.samples as u32).collect();
+    }
+
+    // Runs `shade` once on the state of every pixel, a tile at a time, then stores each pixel's
+    // `estimate` in `image`. Tiles are handed out in `tile_order` from a shared queue so they
+    // finish roughly in that order, and each finished tile is passed to the progress reporter.
+    fn render_pass<T: Clone + Send + Sync>(
+        &self,
+        pass: usize,
+        state: &mut [T],
+        image: &mut Framebuffer,
+        shade: impl Fn(usize, &mut T) + Sync,
+        estimate: impl Fn(&T) -> Colour + Sync,
+    ) {
+        let (width, height) = (image.width(), image.height());
+        let tiles = tile::tiles(width, height, self.tile_size, self.tile_order);
+        if let Some(progress) = &self.progress {
+            progress.begin_pass(pass, tiles.len());
+        }
+
+        let next = AtomicUsize::new(0);
+        let finished = Mutex::new(Vec::with_capacity(tiles.len()));
+        let shared: &[T] = state;
+        rayon::broadcast(|_| {
+            while let Some(tile) = tiles.get(next.fetch_add(1, Ordering::Relaxed)) {
+                let mut tile_state: Vec<T> = tile
+                    .pixel_indices(width)
+                    .map(|index| shared[index].clone())
+                    .collect();
+                for (index, pixel) in tile.pixel_indices(width).zip(&mut tile_state) {
+                    shade(index, pixel);
+                }
+
+                if let Some(progress) = &self.progress {
+                    let pixels: Vec<Colour> = tile_state.iter().map(&estimate).collect();
+                    progress.tile_done(tile, &pixels);
+                }
+                finished.lock().unwrap().push((*tile, tile_state));
+            }
+        });
+
+        for (tile, tile_state) in finished.into_inner().unwrap() {
+            for (index, pixel) in tile.pixel_indices(width).zip(tile_state) {
+                image.pixels_mut()[index] = estimate(&pixel);
+                state[index] = pixel;
+            }
+        }
+        if let Some(progress) = &self.progress {
+            progress.end_pass(pass, image);
+        }
+    }
+
+    // Traces the given samples of a pixel, passing each sample's colour to `accumulate`
+    fn sample_pixel(
+        &self,
+        world: &Arc<dyn Hittable>,
+        index: usize,
+        samples: Range<i32>,
+        mut accumulate: impl FnMut(Colour),
+    ) {
+        let i = index as i32 % self.image_width;
+        let j = index as i32 / self.image_width;
 
-        print!("P3\n{} {}\n255\n", self.image_width, self.image_height);
-        for pixel_colour in image {
-            write_colour(self.pixel_sample_scale * pixel_colour);
+        let max_samples = match self.adaptive {
+            Some(adaptive) => adaptive.max_samples,
+            None => self.samples_per_pixel,
+        };
+        let mut sampler = self.sampler.create(self.seed, max_samples as u32);
+        for sample in samples {
+            seed_random(Pcg32::for_sample(
+                self.seed ^ MEDIUM_STREAM_KEY,
+                index as u64,
+                sample as u64,
+            ));
+            sampler.start_sample(i as u32, j as u32, sample as u32);
+            let r = self.get_ray(i, j, sampler.as_mut());
+            accumulate(self.ray_colour(&r, self.max_depth, world.clone(), sampler.as_mut(), None));
         }
-        eprintln!("\rDone!");
     }
 
-    fn initialize(&mut self) {
+    fn initialize(&mut self, world: &Arc<dyn Hittable>) {
         self.image_height = (self.image_width as f64 / self.aspect_ratio) as i32;
         self.image_height = if self.image_height < 1 {
             1
@@ -97,6 +592,11 @@ impl Camera {
 
         self.pixel_sample_scale = 1.0 / self.samples_per_pixel as f64;
 
+        self.lights = HittableList::new();
+        if self.light_sampling {
+            self.lights.add_lights(world);
+        }
+
         self.centre = self.lookfrom;
 
         // Camera
@@ -129,39 +629,102 @@ impl Camera {
         self.defocus_disk_v = self.v * defocus_radius;
     }
 
-    fn ray_colour(ray: &Ray, depth: i32, world: Arc<dyn Hittable>) -> Colour {
+    // `scatter_pdf` is the density with which the previous bounce picked this ray, given when
+    // that bounce also sampled the lights. Emission found here is then weighted against the
+    // chance of the light sample finding it.
+    fn ray_colour(
+        &self,
+        ray: &Ray,
+        depth: i32,
+        world: Arc<dyn Hittable>,
+        sampler: &mut dyn Sampler,
+        scatter_pdf: Option<f64>,
+    ) -> Colour {
         // Hit ray bounce limit
         if depth <= 0 {
             return Colour::zero();
         }
 
         let mut rec = HitRecord::default();
-        if world.hit(ray, Interval::new(0.001, INFINITY), &mut rec) {
-            let mut scattered = Ray::default();
-            let mut attenuation = Colour::default();
-            // WARN may panic in unwrap()
-            if rec
-                .mat
-                .as_ref()
-                .unwrap()
-                .scatter(ray, &rec, &mut attenuation, &mut scattered)
-            {
-                return attenuation * Self::ray_colour(&scattered, depth - 1, world.clone());
-            }
+        if !world.hit(ray, Interval::new(0.001, INFINITY), &mut rec) {
+            return self.background.colour(ray);
+        }
+
+        // A hit without a material absorbs the ray
+        let Some(mat) = rec.mat.as_ref() else {
+            return Colour::zero();
+        };
+        let mut emission = mat.emitted(&rec);
+        if let Some(scatter_pdf) = scatter_pdf
+            && !emission.near_zero()
+        {
+            let light_pdf = self.lights.pdf_value(ray.origin, ray.direction, ray.time);
+            emission = power_heuristic(scatter_pdf, light_pdf) * emission;
+        }
+
+        let Some(srec) = mat.scatter(ray, &rec, sampler) else {
+            return emission;
+        };
+
+        // Specular bounces can't be lit by light samples, only by the ray they scatter
+        let Some(pdf) = srec.pdf else {
+            let indirect =
+                self.ray_colour(&srec.scattered, depth - 1, world.clone(), sampler, None);
+            return emission + srec.attenuation * indirect;
+        };
+
+        let direct = self.direct_light(&world, ray, &rec, mat.as_ref(), sampler);
+        let next_pdf = if self.lights.is_empty() {
+            None
+        } else {
+            Some(pdf)
+        };
+        let indirect =
+            self.ray_colour(&srec.scattered, depth - 1, world.clone(), sampler, next_pdf);
+        emission + direct + srec.attenuation * indirect
+    }
+
+    // Light reaching a non-specular hit straight from a randomly chosen point on a light,
+    // weighted against the chance of the scattered ray finding the same light
+    fn direct_light(
+        &self,
+        world: &Arc<dyn Hittable>,
+        ray: &Ray,
+        rec: &HitRecord,
+        mat: &dyn Material,
+        sampler: &mut dyn Sampler,
+    ) -> Colour {
+        if self.lights.is_empty() {
+            return Colour::zero();
+        }
+
+        let direction = self.lights.random(rec.p, ray.time, sampler);
+        let light_pdf = self.lights.pdf_value(rec.p, direction, ray.time);
+        let unit_direction = direction.unit();
+        let bsdf = mat.eval(ray, rec, unit_direction);
+        if light_pdf <= 0.0 || bsdf.near_zero() {
             return Colour::zero();
         }
 
-        // Background colour
-        let unit_direction = ray.direction.unit();
-        let a = 0.5 * (unit_direction.y + 1.0);
-        (1.0 - a) * Colour::new(1.0, 1.0, 1.0) + a * Colour::new(0.5, 0.7, 1.0)
+        // Shadow ray; whatever emitter it reaches first is the light seen from here
+        let mut light_rec = HitRecord::default();
+        let shadow = Ray::with_time(rec.p, direction, ray.time);
+        if !world.hit(&shadow, Interval::new(0.001, INFINITY), &mut light_rec) {
+            return Colour::zero();
+        }
+        let Some(light_mat) = light_rec.mat.as_ref() else {
+            return Colour::zero();
+        };
+        let emitted = light_mat.emitted(&light_rec);
+        let weight = power_heuristic(light_pdf, mat.pdf(ray, rec, unit_direction));
+        weight / light_pdf * bsdf * emitted
     }
 
-    fn get_ray(&self, i: i32, j: i32) -> Ray {
+    fn get_ray(&self, i: i32, j: i32, sampler: &mut dyn Sampler) -> Ray {
         // Construct a camera ray originating from the defocus disk and directed at randomly sampled
-        // point around the pixel location i, j.
+        // point around the pixel location i, j, at a random time while the shutter is open.
 
-        let offset = Camera::sample_square();
+        let offset = Camera::sample_square(sampler);
         let pixel_sample = self.pixel00_loc
             + (i as f64 + offset.x) * self.pixel_delta_u
             + (j as f64 + offset.y) * self.pixel_delta_v;
@@ -169,19 +732,75 @@ impl Camera {
         let ray_origin = if self.defocus_angle <= 0.0 {
             self.centre
         } else {
-            self.defocus_disk_sample()
+            self.defocus_disk_sample(sampler)
         };
         let ray_direction = pixel_sample - ray_origin;
+        let ray_time =
+            self.shutter_open + sampler.next_1d() * (self.shutter_close - self.shutter_open);
 
-        Ray::new(ray_origin, ray_direction)
+        Ray::with_time(ray_origin, ray_direction, ray_time)
     }
 
-    fn sample_square() -> Vec3 {
-        Vec3::new(random_f64() - 0.5, random_f64() - 0.5, 0.0)
+    fn sample_square(sampler: &mut dyn Sampler) -> Vec3 {
+        let (u, v) = sampler.next_2d();
+        Vec3::new(u - 0.5, v - 0.5, 0.0)
     }
 
-    fn defocus_disk_sample(&self) -> Point3 {
-        let p = random_in_unit_disk();
+    fn defocus_disk_sample(&self, sampler: &mut dyn Sampler) -> Point3 {
+        let (u, v) = sampler.next_2d();
+        let p = sample_unit_disk(u, v);
         self.centre + p.x * self.defocus_disk_u + p.y * self.defocus_disk_v
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::HittableList;
+    use crate::hittable::constant_medium::ConstantMedium;
+    use crate::hittable::sphere::Sphere;
+    use crate::material::dielectric::Dielectric;
+    use crate::material::lambertian::Lambertian;
+    use crate::material::metal::Metal;
+
+    fn render_with_threads(threads: usize) -> Vec<u64> {
+        let mut world = HittableList::new();
+        let ground = Arc::new(Lambertian::new(Colour::new(0.5, 0.5, 0.5)));
+        world.add(Sphere::new(Point3::new(0.0, -100.5, -1.0), 100.0, ground));
+        let metal = Arc::new(Metal::new(Colour::new(0.8, 0.6, 0.2), 0.3));
+        world.add(Sphere::new(Point3::new(1.0, 0.0, -1.0), 0.5, metal));
+        let glass = Arc::new(Dielectric::new(1.5));
+        world.add(Sphere::new(
+            Point3::new(-1.0, 0.0, -1.0),
+            0.5,
+            glass.clone(),
+        ));
+        let fog = Sphere::new(Point3::new(0.0, 0.0, -1.0), 0.5, glass);
+        world.add(ConstantMedium::new(fog, 2.0, Colour::new(0.9, 0.9, 0.9)));
+
+        let mut cam = Camera::new();
+        cam.image_width = 24;
+        cam.aspect_ratio = 1.5;
+        cam.samples_per_pixel = 4;
+        cam.max_depth = 8;
+        cam.seed = 7;
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        let image = pool.install(|| cam.render(Arc::new(world)));
+        image
+            .pixels()
+            .iter()
+            .flat_map(|p| [p.x.to_bits(), p.y.to_bits(), p.z.to_bits()])
+            .collect()
+    }
+
+    #[test]
+    fn renders_identically_on_any_number_of_threads() {
+        let single = render_with_threads(1);
+        assert_eq!(single, render_with_threads(4));
+        assert_eq!(single, render_with_threads(7));
+    }
+}
@@ -0,0 +1,302 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::hash::Hasher;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::colour::Colour;
+
+const MAGIC: &[u8; 4] = b"RTCK";
+const VERSION: u32 = 1;
+const HEADER_BYTES: u64 = 4 + 4 + 8 + 4 + 4;
+const PIXEL_BYTES: u64 = 4 + 3 * 8;
+
+// Accumulated state of an unfinished render: per-pixel colour sums and sample counts, tagged
+// with the fingerprint of the scene and camera that produced them. Samplers derive every value
+// from the seed, pixel and sample index, so the counts are all the sampler state needed to
+// continue exactly where the render stopped.
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    pub fingerprint: u64,
+    pub width: usize,
+    pub height: usize,
+    pub samples: Vec<u32>,
+    pub sums: Vec<Colour>,
+}
+
+// When and where `Camera::render_progressive` saves checkpoints. An existing checkpoint at
+// `path` is resumed from if its fingerprint matches.
+#[derive(Debug, Clone)]
+pub struct CheckpointSettings {
+    pub path: PathBuf,
+    pub interval: Duration, // Minimum time between saves; the final pass is always saved
+    pub scene_fingerprint: u64, // Identifies the scene; combined with the camera settings
+}
+
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io { path: PathBuf, source: io::Error },
+    Format { path: PathBuf, message: String },
+    // The checkpoint belongs to another scene, camera setup or resolution
+    Mismatch { path: PathBuf },
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckpointError::Io { path, source } => {
+                write!(f, "checkpoint {}: {}", path.display(), source)
+            }
+            CheckpointError::Format { path, message } => {
+                write!(f, "checkpoint {}: {}", path.display(), message)
+            }
+            CheckpointError::Mismatch { path } => write!(
+                f,
+                "checkpoint {} was written for a different scene or camera",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl Error for CheckpointError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CheckpointError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl Checkpoint {
+    // Writes to a temporary file first and renames it over `path`, so a render killed while
+    // saving leaves the previous checkpoint intact
+    pub fn save(&self, path: &Path) -> Result<(), CheckpointError> {
+        let io_error = |source| CheckpointError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+
+        let mut out = BufWriter::new(File::create(&tmp).map_err(io_error)?);
+        self.write(&mut out).map_err(io_error)?;
+        out.into_inner()
+            .map_err(|e| io_error(e.into_error()))?
+            .sync_all()
+            .map_err(io_error)?;
+        fs::rename(&tmp, path).map_err(io_error)
+    }
+
+    pub fn load(path: &Path) -> Result<Checkpoint, CheckpointError> {
+        let io_error = |source| CheckpointError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let file = File::open(path).map_err(io_error)?;
+        let len = file.metadata().map_err(io_error)?.len();
+        Checkpoint::read(&mut BufReader::new(file), len).map_err(|e| match e.kind() {
+            io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof => CheckpointError::Format {
+                path: path.to_path_buf(),
+                message: e.to_string(),
+            },
+            _ => CheckpointError::Io {
+                path: path.to_path_buf(),
+                source: e,
+            },
+        })
+    }
+
+    // Little-endian: magic, version, fingerprint, width, height, then per pixel the sample
+    // count followed by the colour sum
+    fn write<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        out.write_all(MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        out.write_all(&self.fingerprint.to_le_bytes())?;
+        out.write_all(&(self.width as u32).to_le_bytes())?;
+        out.write_all(&(self.height as u32).to_le_bytes())?;
+        for (&samples, sum) in self.samples.iter().zip(&self.sums) {
+            out.write_all(&samples.to_le_bytes())?;
+            for c in [sum.x, sum.y, sum.z] {
+                out.write_all(&c.to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    // `len` is the size of the whole input, checked against the header before anything is
+    // allocated for the pixels
+    fn read<R: Read>(input: &mut R, len: u64) -> io::Result<Checkpoint> {
+        let mut magic = [0; 4];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a render checkpoint",
+            ));
+        }
+        let version = read_u32(input)?;
+        if version != VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported checkpoint version {}", version),
+            ));
+        }
+
+        let fingerprint = read_u64(input)?;
+        let width = read_u32(input)? as usize;
+        let height = read_u32(input)? as usize;
+        let expected_len = (width as u64)
+            .checked_mul(height as u64)
+            .and_then(|pixels| pixels.checked_mul(PIXEL_BYTES))
+            .and_then(|bytes| bytes.checked_add(HEADER_BYTES));
+        if expected_len != Some(len) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("file size does not match its {}x{} header", width, height),
+            ));
+        }
+
+        let mut samples = Vec::with_capacity(width * height);
+        let mut sums = Vec::with_capacity(width * height);
+        for _ in 0..width * height {
+            samples.push(read_u32(input)?);
+            let x = f64::from_bits(read_u64(input)?);
+            let y = f64::from_bits(read_u64(input)?);
+            let z = f64::from_bits(read_u64(input)?);
+            sums.push(Colour::new(x, y, z));
+        }
+
+        Ok(Checkpoint {
+            fingerprint,
+            width,
+            height,
+            samples,
+            sums,
+        })
+    }
+}
+
+fn read_u32<R: Read>(input: &mut R) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    input.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(input: &mut R) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    input.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+// 64-bit FNV-1a. Unlike `DefaultHasher` its output is fixed, so fingerprints stay comparable
+// across runs, platforms and toolchain upgrades.
+#[derive(Debug, Clone)]
+pub struct Fingerprint(u64);
+
+impl Fingerprint {
+    pub fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+
+    pub fn write_f64(&mut self, x: f64) {
+        self.write_u64(x.to_bits());
+    }
+}
+
+impl Default for Fingerprint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Fingerprint {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= b as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    // Fixed byte order, so the same values hash alike on every platform
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_checkpoint() -> Checkpoint {
+        Checkpoint {
+            fingerprint: 0x0123_4567_89ab_cdef,
+            width: 3,
+            height: 2,
+            samples: vec![0, 1, 2, 3, 4, u32::MAX],
+            sums: (0..6)
+                .map(|i| Colour::new(i as f64, -0.1 * i as f64, f64::MIN_POSITIVE))
+                .collect(),
+        }
+    }
+
+    fn read_bytes(bytes: &[u8]) -> io::Result<Checkpoint> {
+        Checkpoint::read(&mut &bytes[..], bytes.len() as u64)
+    }
+
+    #[test]
+    fn round_trips_exactly() {
+        let checkpoint = sample_checkpoint();
+        let mut bytes = Vec::new();
+        checkpoint.write(&mut bytes).unwrap();
+        assert_eq!(bytes.len() as u64, HEADER_BYTES + 6 * PIXEL_BYTES);
+
+        let read = read_bytes(&bytes).unwrap();
+        assert_eq!(read.fingerprint, checkpoint.fingerprint);
+        assert_eq!((read.width, read.height), (3, 2));
+        assert_eq!(read.samples, checkpoint.samples);
+        for (a, b) in read.sums.iter().zip(&checkpoint.sums) {
+            for c in 0..3 {
+                assert_eq!(a[c].to_bits(), b[c].to_bits());
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_a_size_mismatch() {
+        let mut bytes = Vec::new();
+        sample_checkpoint().write(&mut bytes).unwrap();
+
+        let truncated = &bytes[..bytes.len() - 1];
+        let err = read_bytes(truncated).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // A huge header must be rejected before anything is allocated for it
+        let mut huge = bytes[..HEADER_BYTES as usize].to_vec();
+        huge[16..24].copy_from_slice(&[0xff; 8]);
+        let err = read_bytes(&huge).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let err = read_bytes(b"P6\n1 1\n255\n\0\0\0").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
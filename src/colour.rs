@@ -1,30 +1,26 @@
-use crate::interval::Interval;
 use crate::vec3::Vec3;
 
 pub type Colour = Vec3;
 
-fn linear_to_gamma(linear_component: f64) -> f64 {
-    if linear_component > 0.0 {
-        return linear_component.sqrt();
-    }
-    return 0.0;
+// Relative luminance of a linear Rec. 709 / sRGB colour
+pub fn luminance(colour: Colour) -> f64 {
+    0.2126 * colour.x + 0.7152 * colour.y + 0.0722 * colour.z
 }
 
-pub fn write_colour(pixel_colour: Colour) {
-    let mut r = pixel_colour.x;
-    let mut g = pixel_colour.y;
-    let mut b = pixel_colour.z;
-
-    // Linear to gamma transform for gamma 2
-    r = linear_to_gamma(r);
-    g = linear_to_gamma(g);
-    b = linear_to_gamma(b);
-
-    // Translate the [0,1] component values to the byte range [0,255]
-    const INTENSITY: Interval = Interval::new(0.000, 0.999);
-    let ir = (256.0 * INTENSITY.clamp(r)) as i32;
-    let ig = (256.0 * INTENSITY.clamp(g)) as i32;
-    let ib = (255.0 * INTENSITY.clamp(b)) as i32;
+// sRGB opto-electronic transfer function, linear [0,1] to encoded [0,1]
+pub fn linear_to_srgb(linear_component: f64) -> f64 {
+    if linear_component <= 0.0031308 {
+        12.92 * linear_component
+    } else {
+        1.055 * linear_component.powf(1.0 / 2.4) - 0.055
+    }
+}
 
-    print!("{} {} {}\n", ir, ig, ib)
+// Inverse of linear_to_srgb
+pub fn srgb_to_linear(encoded_component: f64) -> f64 {
+    if encoded_component <= 0.04045 {
+        encoded_component / 12.92
+    } else {
+        ((encoded_component + 0.055) / 1.055).powf(2.4)
+    }
 }
@@ -0,0 +1,194 @@
+use crate::colour::{Colour, linear_to_srgb, luminance};
+
+// Maps scene-referred linear radiance to display values: exposure, then tone mapping, then the
+// output encoding, then quantization
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayTransform {
+    pub exposure: f64, // In stops, each one doubles the brightness
+    pub tone_map: ToneMap,
+    pub encoding: Encoding,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ToneMap {
+    #[default]
+    Clamp, // No compression, values above 1 clip
+    Reinhard, // x / (1 + x) per channel
+    ReinhardExtended {
+        white_point: f64, // Luminance mapped to pure white
+    },
+    Aces,  // ACES filmic (Stephen Hill's RRT + ODT fit)
+    Hable, // Uncharted 2 filmic curve
+    Agx,   // AgX base look
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    #[default]
+    Srgb,
+    Gamma(f64),
+    Linear,
+}
+
+impl Default for DisplayTransform {
+    fn default() -> Self {
+        Self {
+            exposure: 0.0,
+            tone_map: ToneMap::Clamp,
+            encoding: Encoding::Srgb,
+        }
+    }
+}
+
+impl DisplayTransform {
+    // Display-referred colour with components in [0,1]
+    pub fn apply(&self, colour: Colour) -> Colour {
+        let exposed = 2f64.powf(self.exposure) * sanitize(colour);
+        let mapped = self.tone_map.apply(exposed);
+        let encode = |c: f64| {
+            let c = c.clamp(0.0, 1.0);
+            match self.encoding {
+                Encoding::Srgb => linear_to_srgb(c),
+                // A gamma of zero or less has no inverse, leave the value linear
+                Encoding::Gamma(gamma) if gamma > 0.0 => c.powf(1.0 / gamma),
+                Encoding::Gamma(_) => c,
+                Encoding::Linear => c,
+            }
+        };
+        Colour::new(encode(mapped.x), encode(mapped.y), encode(mapped.z))
+    }
+
+    // Display-referred colour quantized to 8 bits per channel
+    pub fn to_bytes(&self, colour: Colour) -> [u8; 3] {
+        let c = self.apply(colour);
+        let quantize = |x: f64| (255.0 * x).round() as u8;
+        [quantize(c.x), quantize(c.y), quantize(c.z)]
+    }
+}
+
+impl ToneMap {
+    pub fn apply(&self, c: Colour) -> Colour {
+        match *self {
+            ToneMap::Clamp => c,
+            ToneMap::Reinhard => {
+                Colour::new(c.x / (1.0 + c.x), c.y / (1.0 + c.y), c.z / (1.0 + c.z))
+            }
+            ToneMap::ReinhardExtended { white_point } => {
+                let l_in = luminance(c);
+                if l_in <= 0.0 {
+                    return Colour::zero();
+                }
+                // Without a positive white point nothing maps to white, as in plain Reinhard
+                let white_squared = if white_point > 0.0 {
+                    white_point * white_point
+                } else {
+                    f64::INFINITY
+                };
+                let l_out = l_in * (1.0 + l_in / white_squared) / (1.0 + l_in);
+                (l_out / l_in) * c
+            }
+            ToneMap::Aces => aces_fitted(c),
+            ToneMap::Hable => {
+                const EXPOSURE_BIAS: f64 = 2.0;
+                const WHITE: f64 = 11.2;
+                let white_scale = 1.0 / hable_partial(WHITE);
+                let curve = |x: f64| hable_partial(EXPOSURE_BIAS * x) * white_scale;
+                Colour::new(curve(c.x), curve(c.y), curve(c.z))
+            }
+            ToneMap::Agx => agx(c),
+        }
+    }
+}
+
+// Negative, infinite or NaN components would poison the operators below
+fn sanitize(c: Colour) -> Colour {
+    let fix = |x: f64| if x.is_finite() { x.max(0.0) } else { 0.0 };
+    Colour::new(fix(c.x), fix(c.y), fix(c.z))
+}
+
+fn mul3(m: &[[f64; 3]; 3], c: Colour) -> Colour {
+    Colour::new(
+        m[0][0] * c.x + m[0][1] * c.y + m[0][2] * c.z,
+        m[1][0] * c.x + m[1][1] * c.y + m[1][2] * c.z,
+        m[2][0] * c.x + m[2][1] * c.y + m[2][2] * c.z,
+    )
+}
+
+fn aces_fitted(c: Colour) -> Colour {
+    // sRGB to the ACES reference rendering input space, with the RRT saturation baked in
+    const INPUT: [[f64; 3]; 3] = [
+        [0.59719, 0.35458, 0.04823],
+        [0.07600, 0.90834, 0.01566],
+        [0.02840, 0.13383, 0.83777],
+    ];
+    // ODT output back to linear sRGB
+    const OUTPUT: [[f64; 3]; 3] = [
+        [1.60475, -0.53108, -0.07367],
+        [-0.10208, 1.10813, -0.00605],
+        [-0.00327, -0.07276, 1.07602],
+    ];
+    let rrt_and_odt_fit = |v: f64| {
+        let a = v * (v + 0.0245786) - 0.000090537;
+        let b = v * (0.983729 * v + 0.4329510) + 0.238081;
+        a / b
+    };
+
+    let v = mul3(&INPUT, c);
+    let v = Colour::new(
+        rrt_and_odt_fit(v.x),
+        rrt_and_odt_fit(v.y),
+        rrt_and_odt_fit(v.z),
+    );
+    mul3(&OUTPUT, v)
+}
+
+fn hable_partial(x: f64) -> f64 {
+    const A: f64 = 0.15; // Shoulder strength
+    const B: f64 = 0.50; // Linear strength
+    const C: f64 = 0.10; // Linear angle
+    const D: f64 = 0.20; // Toe strength
+    const E: f64 = 0.02; // Toe numerator
+    const F: f64 = 0.30; // Toe denominator
+    ((x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F)) - E / F
+}
+
+fn agx(c: Colour) -> Colour {
+    // Inset into the AgX working space
+    const INSET: [[f64; 3]; 3] = [
+        [0.842479062253094, 0.0784335999999992, 0.0792237451477643],
+        [0.0423282422610123, 0.878468636469772, 0.0791661274605434],
+        [0.0423756549057051, 0.0784336, 0.879142973793104],
+    ];
+    const OUTSET: [[f64; 3]; 3] = [
+        [1.19687900512017, -0.0980208811401368, -0.0990297440797205],
+        [-0.0528968517574562, 1.15190312990417, -0.0989611768448433],
+        [-0.0529716355144438, -0.0980434501171241, 1.15107367264116],
+    ];
+    const MIN_EV: f64 = -12.47393;
+    const MAX_EV: f64 = 4.026069;
+
+    // Log2 encoding over the dynamic range of the sigmoid
+    let log_encode = |x: f64| {
+        let ev = if x > 0.0 { x.log2() } else { MIN_EV };
+        (ev.clamp(MIN_EV, MAX_EV) - MIN_EV) / (MAX_EV - MIN_EV)
+    };
+    // Polynomial fit of the default AgX contrast sigmoid
+    let contrast = |x: f64| {
+        let x2 = x * x;
+        let x4 = x2 * x2;
+        15.5 * x4 * x2 - 40.14 * x4 * x + 31.96 * x4 - 6.868 * x2 * x + 0.4298 * x2 + 0.1191 * x
+            - 0.00232
+    };
+
+    let v = mul3(&INSET, c);
+    let v = Colour::new(
+        contrast(log_encode(v.x)),
+        contrast(log_encode(v.y)),
+        contrast(log_encode(v.z)),
+    );
+    let v = mul3(&OUTSET, v);
+
+    // The sigmoid output is display encoded, return to linear for the output encoding
+    let linearize = |x: f64| x.max(0.0).powf(2.2);
+    Colour::new(linearize(v.x), linearize(v.y), linearize(v.z))
+}
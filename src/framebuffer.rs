@@ -0,0 +1,72 @@
+use crate::colour::Colour;
+
+// Linear colour pixels stored row by row from the top left
+#[derive(Debug, Clone, Default)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<Colour>,
+}
+
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![Colour::zero(); width * height],
+        }
+    }
+
+    pub fn from_pixels(width: usize, height: usize, pixels: Vec<Colour>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width * height,
+            "framebuffer needs width * height pixels"
+        );
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[Colour] {
+        &self.pixels
+    }
+
+    pub fn pixels_mut(&mut self) -> &mut [Colour] {
+        &mut self.pixels
+    }
+
+    pub fn into_pixels(self) -> Vec<Colour> {
+        self.pixels
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Colour {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, colour: Colour) {
+        self.pixels[y * self.width + x] = colour;
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[Colour]> {
+        self.pixels.chunks(self.width.max(1))
+    }
+
+    // Changes the dimensions, clearing every pixel to black
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.pixels.clear();
+        self.pixels.resize(width * height, Colour::zero());
+    }
+}
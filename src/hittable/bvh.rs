@@ -0,0 +1,167 @@
+use std::sync::Arc;
+
+use crate::aabb::{Aabb, EMPTY_AABB};
+use crate::hittable::{HitRecord, Hittable, HittableList};
+use crate::interval::Interval;
+use crate::ray::Ray;
+
+// Number of centroid buckets evaluated per axis by the surface area heuristic
+const SAH_BUCKETS: usize = 12;
+
+pub struct BvhNode {
+    left: Arc<dyn Hittable>,
+    right: Arc<dyn Hittable>,
+    bbox: Aabb,
+}
+
+impl BvhNode {
+    pub fn new(list: HittableList) -> Self {
+        Self::from_objects(list.objects)
+    }
+
+    pub fn from_objects(mut objects: Vec<Arc<dyn Hittable>>) -> Self {
+        let bbox = objects.iter().fold(EMPTY_AABB, |acc, obj| {
+            Aabb::surrounding(acc, obj.bounding_box())
+        });
+
+        // Unbounded objects such as planes cannot be partitioned, test them separately
+        let (bounded, unbounded): (Vec<_>, Vec<_>) = objects
+            .iter()
+            .cloned()
+            .partition(|obj| obj.bounding_box().is_finite());
+        if !bounded.is_empty() && !unbounded.is_empty() {
+            let mut rest = HittableList::new();
+            for obj in unbounded {
+                rest.add_shared(obj);
+            }
+            return Self {
+                left: Self::build(bounded),
+                right: Arc::new(rest),
+                bbox,
+            };
+        }
+
+        let (left, right): (Arc<dyn Hittable>, Arc<dyn Hittable>) = match objects.len() {
+            0 => {
+                let empty: Arc<dyn Hittable> = Arc::new(HittableList::new());
+                (empty.clone(), empty)
+            }
+            1 => (objects[0].clone(), objects[0].clone()),
+            2 => (objects[0].clone(), objects[1].clone()),
+            _ => {
+                let mid = Self::partition(&mut objects);
+                let right_objects = objects.split_off(mid);
+                (Self::build(objects), Self::build(right_objects))
+            }
+        };
+
+        Self { left, right, bbox }
+    }
+
+    fn build(objects: Vec<Arc<dyn Hittable>>) -> Arc<dyn Hittable> {
+        if objects.len() == 1 {
+            return objects[0].clone();
+        }
+        Arc::new(Self::from_objects(objects))
+    }
+
+    // Reorders objects along the split axis chosen by the SAH and returns the split index
+    fn partition(objects: &mut [Arc<dyn Hittable>]) -> usize {
+        let n = objects.len();
+        let centroid_bounds = objects.iter().fold(EMPTY_AABB, |acc, obj| {
+            let c = obj.bounding_box().centroid();
+            Aabb::surrounding(acc, Aabb::from_points(c, c))
+        });
+
+        let mut best: Option<(f64, usize, f64)> = None; // (cost, axis, split position)
+        for axis in 0..3 {
+            let bounds = centroid_bounds.axis_interval(axis);
+            if bounds.size() <= 0.0 {
+                continue;
+            }
+
+            let mut counts = [0usize; SAH_BUCKETS];
+            let mut boxes = [EMPTY_AABB; SAH_BUCKETS];
+            for obj in objects.iter() {
+                let bbox = obj.bounding_box();
+                let b = Self::bucket_index(centroid(&bbox, axis), bounds);
+                counts[b] += 1;
+                boxes[b] = Aabb::surrounding(boxes[b], bbox);
+            }
+
+            for split in 1..SAH_BUCKETS {
+                let (mut left_box, mut right_box) = (EMPTY_AABB, EMPTY_AABB);
+                let (mut left_count, mut right_count) = (0, 0);
+                for b in 0..split {
+                    left_box = Aabb::surrounding(left_box, boxes[b]);
+                    left_count += counts[b];
+                }
+                for b in split..SAH_BUCKETS {
+                    right_box = Aabb::surrounding(right_box, boxes[b]);
+                    right_count += counts[b];
+                }
+                if left_count == 0 || right_count == 0 {
+                    continue;
+                }
+
+                let cost = left_count as f64 * left_box.surface_area()
+                    + right_count as f64 * right_box.surface_area();
+                if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                    let position = bounds.min + bounds.size() * split as f64 / SAH_BUCKETS as f64;
+                    best = Some((cost, axis, position));
+                }
+            }
+        }
+
+        let Some((_, axis, position)) = best else {
+            // All centroids coincide, any split is as good as another
+            return n / 2;
+        };
+
+        objects.sort_by(|a, b| {
+            centroid(&a.bounding_box(), axis).total_cmp(&centroid(&b.bounding_box(), axis))
+        });
+        let mid = objects.partition_point(|obj| centroid(&obj.bounding_box(), axis) < position);
+        if mid == 0 || mid == n { n / 2 } else { mid }
+    }
+
+    fn bucket_index(c: f64, bounds: Interval) -> usize {
+        let b = (SAH_BUCKETS as f64 * (c - bounds.min) / bounds.size()) as usize;
+        b.min(SAH_BUCKETS - 1)
+    }
+}
+
+fn centroid(bbox: &Aabb, axis: usize) -> f64 {
+    let interval = bbox.axis_interval(axis);
+    0.5 * (interval.min + interval.max)
+}
+
+impl Hittable for BvhNode {
+    fn hit(&self, ray: &Ray, t_range: Interval, rec: &mut HitRecord) -> bool {
+        if !self.bbox.hit(ray, t_range) {
+            return false;
+        }
+
+        let hit_left = self.left.hit(ray, t_range, rec);
+        if Arc::ptr_eq(&self.left, &self.right) {
+            return hit_left;
+        }
+        let right_max = if hit_left { rec.t } else { t_range.max };
+        let hit_right = self
+            .right
+            .hit(ray, Interval::new(t_range.min, right_max), rec);
+
+        hit_left || hit_right
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn collect_lights(&self, lights: &mut HittableList) {
+        lights.add_lights(&self.left);
+        if !Arc::ptr_eq(&self.left, &self.right) {
+            lights.add_lights(&self.right);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use crate::INFINITY;
+use crate::aabb::Aabb;
+use crate::colour::Colour;
+use crate::hittable::{HitRecord, Hittable};
+use crate::interval::{Interval, UNIVERSE_INTERVAL};
+use crate::material::Material;
+use crate::material::isotropic::Isotropic;
+use crate::random_f64;
+use crate::ray::Ray;
+use crate::texture::Texture;
+use crate::vec3::Vec3;
+
+// Volume of uniform density filling a convex boundary, such as smoke in a box or fog in a sphere
+pub struct ConstantMedium<H: Hittable> {
+    boundary: H,
+    neg_inv_density: f64,
+    phase_function: Arc<dyn Material>,
+}
+
+impl<H: Hittable> ConstantMedium<H> {
+    pub fn new(boundary: H, density: f64, albedo: Colour) -> Self {
+        Self::with_phase_function(boundary, density, Arc::new(Isotropic::new(albedo)))
+    }
+
+    pub fn from_texture(boundary: H, density: f64, tex: Arc<dyn Texture>) -> Self {
+        Self::with_phase_function(boundary, density, Arc::new(Isotropic::from_texture(tex)))
+    }
+
+    pub fn with_phase_function(
+        boundary: H,
+        density: f64,
+        phase_function: Arc<dyn Material>,
+    ) -> Self {
+        Self {
+            boundary,
+            neg_inv_density: -1.0 / density,
+            phase_function,
+        }
+    }
+}
+
+impl<H: Hittable> Hittable for ConstantMedium<H> {
+    fn hit(&self, ray: &Ray, t_range: Interval, rec: &mut HitRecord) -> bool {
+        // Find where the ray enters and leaves the boundary
+        let mut rec1 = HitRecord::default();
+        let mut rec2 = HitRecord::default();
+
+        if !self.boundary.hit(ray, UNIVERSE_INTERVAL, &mut rec1) {
+            return false;
+        }
+        if !self
+            .boundary
+            .hit(ray, Interval::new(rec1.t + 0.0001, INFINITY), &mut rec2)
+        {
+            return false;
+        }
+
+        let t_enter = rec1.t.max(t_range.min).max(0.0);
+        let t_exit = rec2.t.min(t_range.max);
+        if t_enter >= t_exit {
+            return false;
+        }
+
+        // Sample the distance to the next scattering event
+        let ray_length = ray.direction.length();
+        let distance_inside_boundary = (t_exit - t_enter) * ray_length;
+        let hit_distance = self.neg_inv_density * random_f64().ln();
+        if hit_distance > distance_inside_boundary {
+            return false;
+        }
+
+        rec.t = t_enter + hit_distance / ray_length;
+        rec.p = ray.at(rec.t);
+        rec.u = 0.0;
+        rec.v = 0.0;
+
+        // Arbitrary, a medium has no surface
+        rec.normal = Vec3::new(1.0, 0.0, 0.0);
+        rec.front_face = true;
+        rec.mat = Some(self.phase_function.clone());
+
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.boundary.bounding_box()
+    }
+}
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::hittable::quad::Quad;
+use crate::hittable::{HitRecord, Hittable, HittableList};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vec3::{Point3, Vec3};
+
+// Axis-aligned box built from six quads
+pub struct Cuboid {
+    sides: HittableList,
+}
+
+impl Cuboid {
+    // Box spanning the two opposite vertices a and b
+    pub fn new(a: Point3, b: Point3, mat: Arc<dyn Material>) -> Self {
+        let mut sides = HittableList::new();
+
+        let min = Point3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z));
+        let max = Point3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z));
+
+        let dx = Vec3::new(max.x - min.x, 0.0, 0.0);
+        let dy = Vec3::new(0.0, max.y - min.y, 0.0);
+        let dz = Vec3::new(0.0, 0.0, max.z - min.z);
+
+        // front
+        sides.add(Quad::new(
+            Point3::new(min.x, min.y, max.z),
+            dx,
+            dy,
+            mat.clone(),
+        ));
+        // right
+        sides.add(Quad::new(
+            Point3::new(max.x, min.y, max.z),
+            -dz,
+            dy,
+            mat.clone(),
+        ));
+        // back
+        sides.add(Quad::new(
+            Point3::new(max.x, min.y, min.z),
+            -dx,
+            dy,
+            mat.clone(),
+        ));
+        // left
+        sides.add(Quad::new(
+            Point3::new(min.x, min.y, min.z),
+            dz,
+            dy,
+            mat.clone(),
+        ));
+        // top
+        sides.add(Quad::new(
+            Point3::new(min.x, max.y, max.z),
+            dx,
+            -dz,
+            mat.clone(),
+        ));
+        // bottom
+        sides.add(Quad::new(Point3::new(min.x, min.y, min.z), dx, dz, mat));
+
+        Self { sides }
+    }
+}
+
+impl Hittable for Cuboid {
+    fn hit(&self, ray: &Ray, t_range: Interval, rec: &mut HitRecord) -> bool {
+        self.sides.hit(ray, t_range, rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.sides.bounding_box()
+    }
+
+    fn collect_lights(&self, lights: &mut HittableList) {
+        self.sides.collect_lights(lights);
+    }
+}
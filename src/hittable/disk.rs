@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use crate::PI;
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable, area_light_pdf};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::onb::Onb;
+use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::vec3::{Point3, Vec3};
+
+pub struct Disk {
+    centre: Point3,
+    radius: f64,
+    basis: Onb, // w is the disk normal
+    mat: Arc<dyn Material>,
+    bbox: Aabb,
+}
+
+impl Disk {
+    pub fn new(centre: Point3, normal: Vec3, radius: f64, mat: Arc<dyn Material>) -> Self {
+        let basis = Onb::new(normal);
+        let radius = f64::max(0.0, radius);
+
+        // Half extent of a disk along each axis is radius * sqrt(1 - n_axis^2)
+        let n = basis.w;
+        let extent = radius
+            * Vec3::new(
+                (1.0 - n.x * n.x).max(0.0).sqrt(),
+                (1.0 - n.y * n.y).max(0.0).sqrt(),
+                (1.0 - n.z * n.z).max(0.0).sqrt(),
+            );
+
+        Self {
+            centre,
+            radius,
+            basis,
+            mat,
+            bbox: Aabb::from_points(centre - extent, centre + extent),
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, ray: &Ray, t_range: Interval, rec: &mut HitRecord) -> bool {
+        let denom = self.basis.w.dot(ray.direction);
+        if denom.abs() < 1e-8 {
+            return false;
+        }
+
+        let t = self.basis.w.dot(self.centre - ray.origin) / denom;
+        if !t_range.contains(t) {
+            return false;
+        }
+
+        let intersection = ray.at(t);
+        let offset = intersection - self.centre;
+        let r_squared = offset.length_squared();
+        if r_squared > self.radius * self.radius {
+            return false;
+        }
+
+        // Polar texture coordinates: u around the centre, v outwards from it
+        let x = offset.dot(self.basis.u);
+        let y = offset.dot(self.basis.v);
+        let phi = y.atan2(x) + PI;
+
+        rec.t = t;
+        rec.p = intersection;
+        rec.u = phi / (2.0 * PI);
+        rec.v = if self.radius > 0.0 {
+            r_squared.sqrt() / self.radius
+        } else {
+            0.0
+        };
+        rec.mat = Some(self.mat.clone());
+        rec.set_face_normal(ray, self.basis.w);
+
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f64) -> f64 {
+        area_light_pdf(
+            self,
+            PI * self.radius * self.radius,
+            origin,
+            direction,
+            time,
+        )
+    }
+
+    // Uniform by area: the square root spreads points evenly over the rings
+    fn random(&self, origin: Point3, _time: f64, sampler: &mut dyn Sampler) -> Vec3 {
+        let (u, v) = sampler.next_2d();
+        let r = self.radius * u.sqrt();
+        let (sin, cos) = (2.0 * PI * v).sin_cos();
+        self.centre + r * cos * self.basis.u + r * sin * self.basis.v - origin
+    }
+
+    fn is_light(&self) -> bool {
+        self.mat.is_emissive()
+    }
+}
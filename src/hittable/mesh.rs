@@ -0,0 +1,222 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::hittable::bvh::BvhNode;
+use crate::hittable::triangle::{intersect, sample_triangle, triangle_bounding_box};
+use crate::hittable::{HitRecord, Hittable, HittableList, area_light_pdf};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::vec3::{Point3, Vec3};
+
+// Vertex and index buffers shared by every triangle of a mesh
+struct MeshData {
+    vertices: Vec<Point3>,
+    normals: Option<Vec<Vec3>>,   // Per-vertex normals for smooth shading
+    uvs: Option<Vec<(f64, f64)>>, // Per-vertex texture coordinates
+    indices: Vec<[usize; 3]>,
+    mat: Arc<dyn Material>,
+}
+
+struct MeshTriangle {
+    mesh: Arc<MeshData>,
+    index: usize,
+}
+
+pub struct TriangleMesh {
+    mesh: Arc<MeshData>,
+    bvh: BvhNode,
+}
+
+impl TriangleMesh {
+    // Flat shaded mesh
+    pub fn new(vertices: Vec<Point3>, indices: Vec<[usize; 3]>, mat: Arc<dyn Material>) -> Self {
+        Self::with_attributes(vertices, None, None, indices, mat)
+    }
+
+    // Smooth shaded mesh, normals are interpolated across each triangle
+    pub fn with_normals(
+        vertices: Vec<Point3>,
+        normals: Vec<Vec3>,
+        indices: Vec<[usize; 3]>,
+        mat: Arc<dyn Material>,
+    ) -> Self {
+        Self::with_attributes(vertices, Some(normals), None, indices, mat)
+    }
+
+    // Mesh with optional per-vertex normals and texture coordinates, each indexed like vertices
+    pub fn with_attributes(
+        vertices: Vec<Point3>,
+        normals: Option<Vec<Vec3>>,
+        uvs: Option<Vec<(f64, f64)>>,
+        indices: Vec<[usize; 3]>,
+        mat: Arc<dyn Material>,
+    ) -> Self {
+        if let Some(normals) = &normals {
+            assert_eq!(
+                vertices.len(),
+                normals.len(),
+                "mesh needs exactly one normal per vertex"
+            );
+        }
+        if let Some(uvs) = &uvs {
+            assert_eq!(
+                vertices.len(),
+                uvs.len(),
+                "mesh needs exactly one texture coordinate per vertex"
+            );
+        }
+        Self::build(MeshData {
+            vertices,
+            normals,
+            uvs,
+            indices,
+            mat,
+        })
+    }
+
+    // Smooth shaded mesh using area weighted averages of the adjacent face normals
+    pub fn smooth(vertices: Vec<Point3>, indices: Vec<[usize; 3]>, mat: Arc<dyn Material>) -> Self {
+        let mut normals = vec![Vec3::zero(); vertices.len()];
+        let mut face_normals = vec![Vec3::zero(); vertices.len()];
+        for &[i0, i1, i2] in indices.iter() {
+            let face_normal = (vertices[i1] - vertices[i0]).cross(vertices[i2] - vertices[i0]);
+            for i in [i0, i1, i2] {
+                normals[i] += face_normal;
+                if face_normals[i].near_zero() {
+                    face_normals[i] = face_normal;
+                }
+            }
+        }
+        for (n, face_normal) in normals.iter_mut().zip(face_normals) {
+            // Opposite faces can cancel out, use one of the faces on its own instead
+            if n.near_zero() {
+                *n = face_normal;
+            }
+            if !n.near_zero() {
+                *n = n.unit();
+            }
+        }
+        Self::with_normals(vertices, normals, indices, mat)
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.mesh.indices.len()
+    }
+
+    fn build(data: MeshData) -> Self {
+        assert!(
+            data.indices
+                .iter()
+                .flatten()
+                .all(|&i| i < data.vertices.len()),
+            "mesh index out of range"
+        );
+
+        let mesh = Arc::new(data);
+        let triangles: Vec<Arc<dyn Hittable>> = (0..mesh.indices.len())
+            .map(|index| {
+                Arc::new(MeshTriangle {
+                    mesh: mesh.clone(),
+                    index,
+                }) as Arc<dyn Hittable>
+            })
+            .collect();
+
+        Self {
+            bvh: BvhNode::from_objects(triangles),
+            mesh,
+        }
+    }
+}
+
+impl Hittable for TriangleMesh {
+    fn hit(&self, ray: &Ray, t_range: Interval, rec: &mut HitRecord) -> bool {
+        self.bvh.hit(ray, t_range, rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bvh.bounding_box()
+    }
+
+    // Each triangle of an emissive mesh is a light of its own
+    fn collect_lights(&self, lights: &mut HittableList) {
+        self.bvh.collect_lights(lights);
+    }
+}
+
+impl MeshTriangle {
+    fn vertices(&self) -> (Point3, Point3, Point3) {
+        let [i0, i1, i2] = self.mesh.indices[self.index];
+        let v = &self.mesh.vertices;
+        (v[i0], v[i1], v[i2])
+    }
+}
+
+impl Hittable for MeshTriangle {
+    fn hit(&self, ray: &Ray, t_range: Interval, rec: &mut HitRecord) -> bool {
+        let (p0, p1, p2) = self.vertices();
+        let Some(hit) = intersect(p0, p1, p2, ray, t_range) else {
+            return false;
+        };
+
+        rec.t = hit.t;
+        rec.p = hit.b0 * p0 + hit.b1 * p1 + hit.b2 * p2;
+        let [i0, i1, i2] = self.mesh.indices[self.index];
+        (rec.u, rec.v) = match &self.mesh.uvs {
+            Some(uvs) => (
+                hit.b0 * uvs[i0].0 + hit.b1 * uvs[i1].0 + hit.b2 * uvs[i2].0,
+                hit.b0 * uvs[i0].1 + hit.b1 * uvs[i1].1 + hit.b2 * uvs[i2].1,
+            ),
+            None => (hit.b1, hit.b2),
+        };
+
+        let mut outward_normal = (p1 - p0).cross(p2 - p0).unit();
+        let interpolated = self
+            .mesh
+            .normals
+            .as_ref()
+            .map(|normals| hit.b0 * normals[i0] + hit.b1 * normals[i1] + hit.b2 * normals[i2]);
+        // Opposing vertex normals can cancel out, leaving only the geometric normal to use
+        match interpolated.filter(|n| !n.near_zero()) {
+            Some(normal) => {
+                let shading_normal = normal.unit();
+                // Keep the geometric normal on the same side as the shading normal
+                if outward_normal.dot(shading_normal) < 0.0 {
+                    outward_normal = -outward_normal;
+                }
+                rec.set_face_normal(ray, outward_normal);
+                rec.normal = if rec.front_face {
+                    shading_normal
+                } else {
+                    -shading_normal
+                };
+            }
+            None => rec.set_face_normal(ray, outward_normal),
+        }
+        rec.mat = Some(self.mesh.mat.clone());
+
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let (p0, p1, p2) = self.vertices();
+        triangle_bounding_box(p0, p1, p2)
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f64) -> f64 {
+        let (p0, p1, p2) = self.vertices();
+        let area = 0.5 * (p1 - p0).cross(p2 - p0).length();
+        area_light_pdf(self, area, origin, direction, time)
+    }
+
+    fn random(&self, origin: Point3, _time: f64, sampler: &mut dyn Sampler) -> Vec3 {
+        let (p0, p1, p2) = self.vertices();
+        sample_triangle(p0, p1, p2, sampler) - origin
+    }
+
+    fn is_light(&self) -> bool {
+        self.mesh.mat.is_emissive()
+    }
+}
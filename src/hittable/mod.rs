@@ -1,11 +1,24 @@
+use std::fmt;
 use std::sync::Arc;
 
+use crate::INFINITY;
+use crate::aabb::Aabb;
 use crate::interval::Interval;
 use crate::material::Material;
 use crate::ray::Ray;
+use crate::sampler::Sampler;
 use crate::vec3::{Point3, Vec3};
 
+pub mod bvh;
+pub mod constant_medium;
+pub mod cuboid;
+pub mod disk;
+pub mod mesh;
+pub mod plane;
+pub mod quad;
 pub mod sphere;
+pub mod transformed;
+pub mod triangle;
 
 #[derive(Default)]
 pub struct HitRecord {
@@ -13,6 +26,9 @@ pub struct HitRecord {
     pub normal: Vec3,
     pub mat: Option<Arc<dyn Material>>,
     pub t: f64,
+    // Surface coordinates of the hit point, barycentric (b1, b2) for untextured triangles
+    pub u: f64,
+    pub v: f64,
     pub front_face: bool,
 }
 
@@ -27,23 +43,52 @@ impl HitRecord {
     }
 }
 
-// TODO: change to Arc if needed
+#[derive(Debug, Default)]
 pub struct HittableList {
-    objects: Vec<Box<dyn Hittable>>,
+    objects: Vec<Arc<dyn Hittable>>,
+    bbox: Aabb,
 }
 
 impl HittableList {
     pub fn new() -> Self {
-        Self { objects: vec![] }
+        Self {
+            objects: vec![],
+            bbox: Aabb::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
     }
 
     pub fn clear(&mut self) {
         self.objects.clear();
+        self.bbox = Aabb::default();
     }
 
     // Some magic is happening here
     pub fn add<T: Hittable + 'static>(&mut self, obj: T) {
-        self.objects.push(Box::new(obj));
+        self.add_shared(Arc::new(obj));
+    }
+
+    // Add an object that may also be referenced elsewhere in the scene
+    pub fn add_shared(&mut self, obj: Arc<dyn Hittable>) {
+        self.bbox = Aabb::surrounding(self.bbox, obj.bounding_box());
+        self.objects.push(obj);
+    }
+
+    // Add the lights in `obj`: the object itself if it is one, otherwise the lights among its
+    // parts
+    pub fn add_lights(&mut self, obj: &Arc<dyn Hittable>) {
+        if obj.is_light() {
+            self.add_shared(obj.clone());
+        } else {
+            obj.collect_lights(self);
+        }
     }
 }
 
@@ -64,12 +109,117 @@ impl Hittable for HittableList {
             }
         }
         // Only works if temp_record is not updated if object misses
-        *rec = temp_record;
+        if hit_anything {
+            *rec = temp_record;
+        }
 
         hit_anything
     }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    // Each object is picked with equal probability
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f64) -> f64 {
+        if self.objects.is_empty() {
+            return 0.0;
+        }
+        let sum: f64 = self
+            .objects
+            .iter()
+            .map(|obj| obj.pdf_value(origin, direction, time))
+            .sum();
+        sum / self.objects.len() as f64
+    }
+
+    fn random(&self, origin: Point3, time: f64, sampler: &mut dyn Sampler) -> Vec3 {
+        let n = self.objects.len();
+        let index = ((sampler.next_1d() * n as f64) as usize).min(n - 1);
+        self.objects[index].random(origin, time, sampler)
+    }
+
+    fn collect_lights(&self, lights: &mut HittableList) {
+        for object in &self.objects {
+            lights.add_lights(object);
+        }
+    }
 }
 
 pub trait Hittable: Sync + Send {
     fn hit(&self, ray: &Ray, t_range: Interval, rec: &mut HitRecord) -> bool;
+
+    fn bounding_box(&self) -> Aabb;
+
+    // Light sampling. `random` returns the vector from `origin` to a random point on the
+    // object, and `pdf_value` the density of that choosing `direction`, per unit solid angle.
+    // Only shapes that can be lights implement these.
+    fn pdf_value(&self, _origin: Point3, _direction: Vec3, _time: f64) -> f64 {
+        0.0
+    }
+
+    fn random(&self, _origin: Point3, _time: f64, _sampler: &mut dyn Sampler) -> Vec3 {
+        Vec3::new(1.0, 0.0, 0.0)
+    }
+
+    // True for an emissive object that can be sampled as a whole
+    fn is_light(&self) -> bool {
+        false
+    }
+
+    // Aggregates pass their parts to `HittableList::add_lights`
+    fn collect_lights(&self, _lights: &mut HittableList) {}
+}
+
+impl fmt::Debug for dyn Hittable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("dyn Hittable")
+    }
+}
+
+// Shared objects, e.g. geometry instanced several times with Transformed
+impl<H: Hittable + ?Sized> Hittable for Arc<H> {
+    fn hit(&self, ray: &Ray, t_range: Interval, rec: &mut HitRecord) -> bool {
+        (**self).hit(ray, t_range, rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        (**self).bounding_box()
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f64) -> f64 {
+        (**self).pdf_value(origin, direction, time)
+    }
+
+    fn random(&self, origin: Point3, time: f64, sampler: &mut dyn Sampler) -> Vec3 {
+        (**self).random(origin, time, sampler)
+    }
+
+    fn is_light(&self) -> bool {
+        (**self).is_light()
+    }
+
+    fn collect_lights(&self, lights: &mut HittableList) {
+        (**self).collect_lights(lights)
+    }
+}
+
+// Solid angle density towards `object` for a flat light sampled uniformly over its area:
+// converting from area divides by the cosine at the light and multiplies by the squared distance
+fn area_light_pdf(
+    object: &dyn Hittable,
+    area: f64,
+    origin: Point3,
+    direction: Vec3,
+    time: f64,
+) -> f64 {
+    let mut rec = HitRecord::default();
+    let ray = Ray::with_time(origin, direction, time);
+    if !object.hit(&ray, Interval::new(0.001, INFINITY), &mut rec) {
+        return 0.0;
+    }
+
+    let distance_squared = rec.t * rec.t * direction.length_squared();
+    let cosine = direction.dot(rec.normal).abs() / direction.length();
+    distance_squared / (cosine * area)
 }
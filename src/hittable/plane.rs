@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use crate::aabb::{Aabb, UNIVERSE_AABB};
+use crate::hittable::{HitRecord, Hittable};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::onb::Onb;
+use crate::ray::Ray;
+use crate::vec3::{Point3, Vec3};
+
+// Infinite plane through a point. Texture coordinates are distances from that point along the
+// plane's tangent directions.
+pub struct Plane {
+    point: Point3,
+    basis: Onb, // w is the plane normal
+    mat: Arc<dyn Material>,
+}
+
+impl Plane {
+    pub fn new(point: Point3, normal: Vec3, mat: Arc<dyn Material>) -> Self {
+        Self {
+            point,
+            basis: Onb::new(normal),
+            mat,
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(&self, ray: &Ray, t_range: Interval, rec: &mut HitRecord) -> bool {
+        let denom = self.basis.w.dot(ray.direction);
+        if denom.abs() < 1e-8 {
+            return false;
+        }
+
+        let t = self.basis.w.dot(self.point - ray.origin) / denom;
+        if !t_range.contains(t) {
+            return false;
+        }
+
+        let intersection = ray.at(t);
+        let offset = intersection - self.point;
+
+        rec.t = t;
+        rec.p = intersection;
+        rec.u = offset.dot(self.basis.u);
+        rec.v = offset.dot(self.basis.v);
+        rec.mat = Some(self.mat.clone());
+        rec.set_face_normal(ray, self.basis.w);
+
+        true
+    }
+
+    // Unbounded, so BVHs keep planes out of their hierarchy
+    fn bounding_box(&self) -> Aabb {
+        UNIVERSE_AABB
+    }
+}
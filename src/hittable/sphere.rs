@@ -1,29 +1,67 @@
 use std::sync::Arc;
 
+use crate::aabb::Aabb;
 use crate::hittable::{HitRecord, Hittable};
 use crate::interval::Interval;
 use crate::material::Material;
-use crate::vec3::Point3;
+use crate::onb::Onb;
+use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::vec3::{Point3, Vec3, sample_unit_vector};
+use crate::{INFINITY, PI};
 
 pub struct Sphere {
-    centre: Point3,
+    centre: Ray, // Centre at time t is centre.at(t)
     radius: f64,
     mat: Arc<dyn Material>,
+    bbox: Aabb,
 }
 
 impl Sphere {
     pub fn new(centre: Point3, radius: f64, mat: Arc<dyn Material>) -> Self {
+        let radius = f64::max(0.0, radius);
+        let rvec = Vec3::new(radius, radius, radius);
         Self {
-            centre,
-            radius: f64::max(0.0, radius),
+            centre: Ray::new(centre, Vec3::zero()),
+            radius,
             mat,
+            bbox: Aabb::from_points(centre - rvec, centre + rvec),
         }
     }
+
+    // Sphere moving linearly from centre1 at time 0 to centre2 at time 1
+    pub fn new_moving(
+        centre1: Point3,
+        centre2: Point3,
+        radius: f64,
+        mat: Arc<dyn Material>,
+    ) -> Self {
+        let radius = f64::max(0.0, radius);
+        let rvec = Vec3::new(radius, radius, radius);
+        let box1 = Aabb::from_points(centre1 - rvec, centre1 + rvec);
+        let box2 = Aabb::from_points(centre2 - rvec, centre2 + rvec);
+        Self {
+            centre: Ray::new(centre1, centre2 - centre1),
+            radius,
+            mat,
+            bbox: Aabb::surrounding(box1, box2),
+        }
+    }
+
+    // p: a given point on the sphere of radius one, centered at the origin
+    // u: returned value [0,1] of angle around the Y axis from X=-1
+    // v: returned value [0,1] of angle from Y=-1 to Y=+1
+    fn get_sphere_uv(p: Point3) -> (f64, f64) {
+        let theta = (-p.y).acos();
+        let phi = (-p.z).atan2(p.x) + PI;
+        (phi / (2.0 * PI), theta / PI)
+    }
 }
 
 impl Hittable for Sphere {
-    fn hit(&self, ray: &crate::ray::Ray, t_range: Interval, rec: &mut HitRecord) -> bool {
-        let oc = self.centre - ray.origin; // C - Q
+    fn hit(&self, ray: &Ray, t_range: Interval, rec: &mut HitRecord) -> bool {
+        let current_centre = self.centre.at(ray.time);
+        let oc = current_centre - ray.origin; // C - Q
         let a = ray.direction.length_squared();
         let h = ray.direction.dot(oc);
         let c = oc.length_squared() - self.radius * self.radius;
@@ -44,10 +82,53 @@ impl Hittable for Sphere {
 
         rec.t = root;
         rec.p = ray.at(rec.t);
-        let outward_normal = (rec.p - self.centre) / self.radius;
+        let outward_normal = (rec.p - current_centre) / self.radius;
         rec.set_face_normal(ray, outward_normal);
+        (rec.u, rec.v) = Self::get_sphere_uv(outward_normal);
         rec.mat = Some(self.mat.clone());
 
-        return true;
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    // Directions are sampled uniformly within the cone the sphere subtends from the origin,
+    // or over all directions from inside it
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f64) -> f64 {
+        let mut rec = HitRecord::default();
+        let ray = Ray::with_time(origin, direction, time);
+        if !self.hit(&ray, Interval::new(0.001, INFINITY), &mut rec) {
+            return 0.0;
+        }
+
+        let distance_squared = (self.centre.at(time) - origin).length_squared();
+        let radius_squared = self.radius * self.radius;
+        if distance_squared <= radius_squared {
+            return 1.0 / (4.0 * PI);
+        }
+        let cos_theta_max = (1.0 - radius_squared / distance_squared).sqrt();
+        1.0 / (2.0 * PI * (1.0 - cos_theta_max))
+    }
+
+    fn random(&self, origin: Point3, time: f64, sampler: &mut dyn Sampler) -> Vec3 {
+        let (u, v) = sampler.next_2d();
+        let direction = self.centre.at(time) - origin;
+        let distance_squared = direction.length_squared();
+        let radius_squared = self.radius * self.radius;
+        if distance_squared <= radius_squared {
+            return sample_unit_vector(u, v);
+        }
+
+        let cos_theta_max = (1.0 - radius_squared / distance_squared).sqrt();
+        let z = 1.0 + v * (cos_theta_max - 1.0);
+        let phi = 2.0 * PI * u;
+        let r = (1.0 - z * z).max(0.0).sqrt();
+        Onb::new(direction).transform(Vec3::new(phi.cos() * r, phi.sin() * r, z))
+    }
+
+    fn is_light(&self) -> bool {
+        self.mat.is_emissive()
     }
 }
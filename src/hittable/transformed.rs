@@ -0,0 +1,168 @@
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable, HittableList};
+use crate::interval::Interval;
+use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::transform::{AnimatedTransform, Transform};
+use crate::vec3::{Point3, Vec3};
+
+// Places an object in the world with an affine transform. Wrap an Arc<dyn Hittable> to
+// instance the same geometry several times.
+pub struct Transformed<H: Hittable> {
+    object: H,
+    transform: Transform,
+    bbox: Aabb,
+}
+
+impl<H: Hittable> Transformed<H> {
+    pub fn new(object: H, transform: Transform) -> Self {
+        let bbox = transform.bbox_to_world(object.bounding_box());
+        Self {
+            object,
+            transform,
+            bbox,
+        }
+    }
+
+    pub fn transform(&self) -> &Transform {
+        &self.transform
+    }
+}
+
+impl<H: Hittable> Hittable for Transformed<H> {
+    fn hit(&self, ray: &Ray, t_range: Interval, rec: &mut HitRecord) -> bool {
+        hit_transformed(&self.object, &self.transform, ray, t_range, rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f64) -> f64 {
+        pdf_transformed(&self.object, &self.transform, origin, direction, time)
+    }
+
+    fn random(&self, origin: Point3, time: f64, sampler: &mut dyn Sampler) -> Vec3 {
+        random_transformed(&self.object, &self.transform, origin, time, sampler)
+    }
+
+    fn is_light(&self) -> bool {
+        self.object.is_light()
+    }
+
+    // Lights inside a transformed group are instanced with the same transform
+    fn collect_lights(&self, lights: &mut HittableList) {
+        let mut parts = HittableList::new();
+        self.object.collect_lights(&mut parts);
+        for light in parts.objects {
+            lights.add(Transformed::new(light, self.transform));
+        }
+    }
+}
+
+// Instance whose transform follows keyframes over time, giving motion blur when the camera
+// shutter spans the animation
+pub struct Animated<H: Hittable> {
+    object: H,
+    animation: AnimatedTransform,
+    bbox: Aabb,
+}
+
+impl<H: Hittable> Animated<H> {
+    pub fn new(object: H, animation: AnimatedTransform) -> Self {
+        let bbox = animation.bbox_to_world(object.bounding_box());
+        Self {
+            object,
+            animation,
+            bbox,
+        }
+    }
+
+    pub fn animation(&self) -> &AnimatedTransform {
+        &self.animation
+    }
+}
+
+impl<H: Hittable> Hittable for Animated<H> {
+    fn hit(&self, ray: &Ray, t_range: Interval, rec: &mut HitRecord) -> bool {
+        let transform = self.animation.at(ray.time);
+        hit_transformed(&self.object, &transform, ray, t_range, rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f64) -> f64 {
+        let transform = self.animation.at(time);
+        pdf_transformed(&self.object, &transform, origin, direction, time)
+    }
+
+    fn random(&self, origin: Point3, time: f64, sampler: &mut dyn Sampler) -> Vec3 {
+        let transform = self.animation.at(time);
+        random_transformed(&self.object, &transform, origin, time, sampler)
+    }
+
+    fn is_light(&self) -> bool {
+        self.object.is_light()
+    }
+
+    fn collect_lights(&self, lights: &mut HittableList) {
+        let mut parts = HittableList::new();
+        self.object.collect_lights(&mut parts);
+        for light in parts.objects {
+            lights.add(Animated::new(light, self.animation.clone()));
+        }
+    }
+}
+
+fn hit_transformed<H: Hittable>(
+    object: &H,
+    transform: &Transform,
+    ray: &Ray,
+    t_range: Interval,
+    rec: &mut HitRecord,
+) -> bool {
+    let object_ray = transform.ray_to_object(ray);
+    if !object.hit(&object_ray, t_range, rec) {
+        return false;
+    }
+
+    // The face orientation is unchanged by an invertible transform
+    rec.p = transform.point_to_world(rec.p);
+    rec.normal = transform.normal_to_world(rec.normal);
+
+    true
+}
+
+// The object's density is per unit solid angle in object space. A linear map A takes the
+// world direction w to A w, which scales solid angles by |det A| / |A w|^3.
+fn pdf_transformed<H: Hittable>(
+    object: &H,
+    transform: &Transform,
+    origin: Point3,
+    direction: Vec3,
+    time: f64,
+) -> f64 {
+    let object_ray = transform.ray_to_object(&Ray::with_time(origin, direction.unit(), time));
+    let inverse = transform.inverse_matrix();
+    let determinant = inverse.transform_vector(Vec3::new(1.0, 0.0, 0.0)).dot(
+        inverse
+            .transform_vector(Vec3::new(0.0, 1.0, 0.0))
+            .cross(inverse.transform_vector(Vec3::new(0.0, 0.0, 1.0))),
+    );
+    let length = object_ray.direction.length();
+    object.pdf_value(object_ray.origin, object_ray.direction, time) * determinant.abs()
+        / (length * length * length)
+}
+
+fn random_transformed<H: Hittable>(
+    object: &H,
+    transform: &Transform,
+    origin: Point3,
+    time: f64,
+    sampler: &mut dyn Sampler,
+) -> Vec3 {
+    let object_origin = transform.inverse_matrix().transform_point(origin);
+    transform.vector_to_world(object.random(object_origin, time, sampler))
+}
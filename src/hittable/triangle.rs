@@ -0,0 +1,239 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable, area_light_pdf};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::vec3::{Point3, Vec3};
+
+pub struct Triangle {
+    p0: Point3,
+    p1: Point3,
+    p2: Point3,
+    mat: Arc<dyn Material>,
+    bbox: Aabb,
+}
+
+impl Triangle {
+    pub fn new(p0: Point3, p1: Point3, p2: Point3, mat: Arc<dyn Material>) -> Self {
+        Self {
+            p0,
+            p1,
+            p2,
+            mat,
+            bbox: triangle_bounding_box(p0, p1, p2),
+        }
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, ray: &Ray, t_range: Interval, rec: &mut HitRecord) -> bool {
+        let Some(hit) = intersect(self.p0, self.p1, self.p2, ray, t_range) else {
+            return false;
+        };
+
+        rec.t = hit.t;
+        rec.p = hit.b0 * self.p0 + hit.b1 * self.p1 + hit.b2 * self.p2;
+        rec.u = hit.b1;
+        rec.v = hit.b2;
+        let outward_normal = (self.p1 - self.p0).cross(self.p2 - self.p0).unit();
+        rec.set_face_normal(ray, outward_normal);
+        rec.mat = Some(self.mat.clone());
+
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f64) -> f64 {
+        let area = 0.5 * (self.p1 - self.p0).cross(self.p2 - self.p0).length();
+        area_light_pdf(self, area, origin, direction, time)
+    }
+
+    fn random(&self, origin: Point3, _time: f64, sampler: &mut dyn Sampler) -> Vec3 {
+        sample_triangle(self.p0, self.p1, self.p2, sampler) - origin
+    }
+
+    fn is_light(&self) -> bool {
+        self.mat.is_emissive()
+    }
+}
+
+pub(crate) struct TriangleHit {
+    pub t: f64,
+    // Barycentric weights of p0, p1 and p2
+    pub b0: f64,
+    pub b1: f64,
+    pub b2: f64,
+}
+
+// Uniform by area: folding the square root into the first weight keeps the density flat
+pub(crate) fn sample_triangle(
+    p0: Point3,
+    p1: Point3,
+    p2: Point3,
+    sampler: &mut dyn Sampler,
+) -> Point3 {
+    let (u, v) = sampler.next_2d();
+    let su = u.sqrt();
+    (1.0 - su) * p0 + su * (1.0 - v) * p1 + su * v * p2
+}
+
+pub(crate) fn triangle_bounding_box(p0: Point3, p1: Point3, p2: Point3) -> Aabb {
+    Aabb::surrounding(Aabb::from_points(p0, p1), Aabb::from_points(p1, p2))
+}
+
+// Watertight ray/triangle intersection (Woop, Benthin and Wald 2013). Rays passing exactly
+// through a shared edge or vertex hit at least one of the adjacent triangles.
+pub(crate) fn intersect(
+    p0: Point3,
+    p1: Point3,
+    p2: Point3,
+    ray: &Ray,
+    t_range: Interval,
+) -> Option<TriangleHit> {
+    // Transform the triangle into a space where the ray starts at the origin and runs along +z
+    let kz = ray.direction.abs().max_dimension();
+    let kx = (kz + 1) % 3;
+    let ky = (kx + 1) % 3;
+    let d = ray.direction.permute(kx, ky, kz);
+    let mut p0t = (p0 - ray.origin).permute(kx, ky, kz);
+    let mut p1t = (p1 - ray.origin).permute(kx, ky, kz);
+    let mut p2t = (p2 - ray.origin).permute(kx, ky, kz);
+
+    let sx = -d.x / d.z;
+    let sy = -d.y / d.z;
+    let sz = 1.0 / d.z;
+    p0t.x += sx * p0t.z;
+    p0t.y += sy * p0t.z;
+    p1t.x += sx * p1t.z;
+    p1t.y += sy * p1t.z;
+    p2t.x += sx * p2t.z;
+    p2t.y += sy * p2t.z;
+
+    // Edge functions, all of the same sign when the ray passes through the triangle
+    let e0 = p1t.x * p2t.y - p1t.y * p2t.x;
+    let e1 = p2t.x * p0t.y - p2t.y * p0t.x;
+    let e2 = p0t.x * p1t.y - p0t.y * p1t.x;
+    if (e0 < 0.0 || e1 < 0.0 || e2 < 0.0) && (e0 > 0.0 || e1 > 0.0 || e2 > 0.0) {
+        return None;
+    }
+    let det = e0 + e1 + e2;
+    if det == 0.0 {
+        return None;
+    }
+
+    let t_scaled = sz * (e0 * p0t.z + e1 * p1t.z + e2 * p2t.z);
+    let inv_det = 1.0 / det;
+    let t = t_scaled * inv_det;
+    if !t_range.surrounds(t) {
+        return None;
+    }
+
+    Some(TriangleHit {
+        t,
+        b0: e0 * inv_det,
+        b1: e1 * inv_det,
+        b2: e2 * inv_det,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3::Vec3;
+
+    fn hits(triangles: &[[Point3; 3]], ray: &Ray) -> usize {
+        triangles
+            .iter()
+            .filter(|[p0, p1, p2]| intersect(*p0, *p1, *p2, ray, Interval::new(0.0, 1e9)).is_some())
+            .count()
+    }
+
+    #[test]
+    fn barycentrics_locate_the_hit_point() {
+        let (p0, p1, p2) = (
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(2.0, 0.0, 0.0),
+            Point3::new(0.0, 2.0, 0.0),
+        );
+        let ray = Ray::new(Point3::new(0.5, 0.25, 3.0), Vec3::new(0.0, 0.0, -1.0));
+        let hit = intersect(p0, p1, p2, &ray, Interval::new(0.0, 10.0)).unwrap();
+
+        assert!((hit.t - 3.0).abs() < 1e-12);
+        assert!((hit.b0 + hit.b1 + hit.b2 - 1.0).abs() < 1e-12);
+        let p = hit.b0 * p0 + hit.b1 * p1 + hit.b2 * p2;
+        assert!((p - ray.at(hit.t)).near_zero());
+    }
+
+    #[test]
+    fn misses_outside_behind_and_parallel() {
+        let tri = [
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+        ];
+        let down = Vec3::new(0.0, 0.0, -1.0);
+        assert_eq!(hits(&[tri], &Ray::new(Point3::new(0.6, 0.6, 1.0), down)), 0);
+        assert_eq!(
+            hits(&[tri], &Ray::new(Point3::new(0.2, 0.2, -1.0), down)),
+            0
+        );
+        let along = Vec3::new(1.0, 0.0, 0.0);
+        assert_eq!(
+            hits(&[tri], &Ray::new(Point3::new(-1.0, 0.2, 0.0), along)),
+            0
+        );
+    }
+
+    #[test]
+    fn rays_through_a_shared_edge_hit() {
+        // Unit square split along its diagonal
+        let a = Point3::new(0.0, 0.0, 0.0);
+        let b = Point3::new(1.0, 0.0, 0.0);
+        let c = Point3::new(1.0, 1.0, 0.0);
+        let d = Point3::new(0.0, 1.0, 0.0);
+        let square = [[a, b, c], [a, c, d]];
+
+        for i in 1..100 {
+            let s = i as f64 / 99.0;
+            for direction in [Vec3::new(0.0, 0.0, -1.0), Vec3::new(0.3, -0.7, -1.0)] {
+                let on_edge = Point3::new(s, s, 0.0);
+                let ray = Ray::new(on_edge - 2.0 * direction, direction);
+                assert!(
+                    hits(&square, &ray) >= 1,
+                    "ray through ({}, {}) leaked",
+                    s,
+                    s
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rays_through_a_shared_vertex_hit() {
+        // Fan of triangles around the origin
+        let ring: Vec<Point3> = (0..7)
+            .map(|k| {
+                let angle = 2.0 * crate::PI * k as f64 / 7.0;
+                Point3::new(angle.cos(), angle.sin(), 0.0)
+            })
+            .collect();
+        let fan: Vec<[Point3; 3]> = (0..7)
+            .map(|k| [Point3::zero(), ring[k], ring[(k + 1) % 7]])
+            .collect();
+
+        for direction in [
+            Vec3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.1, 0.2, -1.0),
+            Vec3::new(-0.4, 0.3, -0.5),
+        ] {
+            let ray = Ray::new(-3.0 * direction, direction);
+            assert!(hits(&fan, &ray) >= 1);
+        }
+    }
+}
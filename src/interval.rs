@@ -20,6 +20,14 @@ impl Interval {
         Self { min, max }
     }
 
+    // Tightest interval enclosing both a and b
+    pub fn enclosing(a: Interval, b: Interval) -> Self {
+        Self {
+            min: a.min.min(b.min),
+            max: a.max.max(b.max),
+        }
+    }
+
     pub fn size(&self) -> f64 {
         self.max - self.min
     }
@@ -41,6 +49,11 @@ impl Interval {
         }
         x
     }
+
+    pub fn expand(&self, delta: f64) -> Self {
+        let padding = delta / 2.0;
+        Self::new(self.min - padding, self.max + padding)
+    }
 }
 
 impl Default for Interval {
@@ -1,28 +1,56 @@
-use rand::prelude::*;
+use std::cell::RefCell;
 
+use rng::Pcg32;
+
+pub mod aabb;
 pub mod camera;
+pub mod checkpoint;
 pub mod colour;
+pub mod display;
+pub mod framebuffer;
 pub mod hittable;
 pub mod interval;
+pub mod mat4;
 pub mod material;
+pub mod obj;
+pub mod onb;
+pub mod output;
+pub mod progress;
 pub mod ray;
+pub mod rng;
+pub mod sampler;
+pub mod scene;
+pub mod scenes;
+pub mod texture;
+pub mod tile;
+pub mod transform;
 pub mod vec3;
 
 // Constants
 pub const INFINITY: f64 = f64::MAX;
-pub const PI: f64 = 3.1415926535897932385;
+pub const PI: f64 = std::f64::consts::PI;
+pub const DEFAULT_SEED: u64 = 0x5eed;
+
+thread_local! {
+    // Every thread starts from the same state, so code run outside a render (scene
+    // construction, for instance) is deterministic as well
+    static RNG: RefCell<Pcg32> = RefCell::new(Pcg32::new(DEFAULT_SEED, 0));
+}
 
 // Utility functions
 pub fn degrees_to_radians(degrees: f64) -> f64 {
     degrees * PI / 180.0
 }
 
+// Replace the current thread's generator; `random_f64` draws from it until the next call
+pub fn seed_random(rng: Pcg32) {
+    RNG.with(|r| *r.borrow_mut() = rng);
+}
+
 pub fn random_f64() -> f64 {
-    let mut rng = rand::rng();
-    rng.random()
+    RNG.with(|r| r.borrow_mut().next_f64())
 }
 
 pub fn random_f64_in_range(min: f64, max: f64) -> f64 {
-    let mut rng = rand::rng();
-    rng.random_range(min..max)
+    RNG.with(|r| r.borrow_mut().next_f64_in_range(min, max))
 }
@@ -1,95 +1,276 @@
+use std::error::Error;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
-use raytracer::camera::Camera;
-use raytracer::colour::Colour;
-use raytracer::hittable::sphere::Sphere;
-use raytracer::hittable::{Hittable, HittableList};
-use raytracer::material::Material;
-use raytracer::material::dielectric::Dielectric;
-use raytracer::material::lambertian::Lambertian;
-use raytracer::material::metal::Metal;
-use raytracer::vec3::{Point3, Vec3};
-use raytracer::{random_f64, random_f64_in_range};
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{Parser, ValueEnum};
+
+use raytracer::camera::Progressive;
+use raytracer::checkpoint::CheckpointSettings;
+use raytracer::display::{DisplayTransform, ToneMap};
+use raytracer::hittable::bvh::BvhNode;
+use raytracer::output::{self, ImageFormat};
+use raytracer::scene::{self, Scene};
+use raytracer::scenes::Preset;
+
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    about = "Renders a TOML scene file or one of the built-in presets",
+    after_help = "Camera settings are applied in order: the scene or preset, then --set, then the \
+                  dedicated flags such as --width and --spp."
+)]
+struct Args {
+    #[arg(help = "Scene file to render", conflicts_with = "preset")]
+    scene: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_parser = PossibleValuesParser::new(Preset::ALL.map(|preset| preset.name()))
+            .map(|name| Preset::from_name(&name).unwrap()),
+        help = "Built-in scene to render instead of a file [default: random-spheres]"
+    )]
+    preset: Option<Preset>,
+
+    #[arg(short, long, default_value = "image.png", help = "Image to write")]
+    output: PathBuf,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Image format [default: inferred from the output extension]"
+    )]
+    format: Option<Format>,
+
+    #[arg(
+        long,
+        value_parser = clap::value_parser!(i32).range(1..),
+        help = "Image width in pixels; the height follows from the aspect ratio"
+    )]
+    width: Option<i32>,
+
+    #[arg(long, value_parser = clap::value_parser!(i32).range(1..), help = "Samples per pixel")]
+    spp: Option<i32>,
+
+    #[arg(
+        long,
+        value_parser = clap::value_parser!(i32).range(1..),
+        help = "Maximum number of bounces per camera ray"
+    )]
+    max_depth: Option<i32>,
+
+    #[arg(long, help = "Worker threads [default: one per CPU]")]
+    threads: Option<usize>,
+
+    #[arg(long, help = "Seed for the camera's sample sequences")]
+    seed: Option<u64>,
+
+    #[arg(
+        long = "set",
+        value_name = "NAME=VALUE",
+        help = "Overrides a camera setting, using the names and values of a scene file's \
+                [camera] table (e.g. vfov=40, lookfrom=13,2,3, sampler=sobol); repeatable"
+    )]
+    settings: Vec<String>,
+
+    #[arg(long, default_value_t = 0.0, help = "Exposure adjustment in stops")]
+    exposure: f64,
+
+    #[arg(long, value_enum, default_value = "clamp", help = "Tone mapping curve")]
+    tone_map: ToneMapArg,
+
+    #[arg(
+        long,
+        default_value_t = 4.0,
+        value_parser = positive_number,
+        help = "Luminance mapped to white by --tone-map reinhard-extended"
+    )]
+    white_point: f64,
+
+    #[arg(
+        long,
+        value_name = "SAMPLES",
+        value_parser = clap::value_parser!(i32).range(1..),
+        help = "Render progressively, adding SAMPLES per pixel each pass and rewriting the image \
+                after every pass"
+    )]
+    progressive: Option<i32>,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Stop before starting a pass that would run past this time; implies --progressive"
+    )]
+    time_limit: Option<f64>,
+
+    #[arg(
+        long,
+        help = "Save render state here and resume from it if it exists; implies --progressive"
+    )]
+    checkpoint: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 60.0,
+        help = "Minimum time between checkpoint saves"
+    )]
+    checkpoint_interval: f64,
+
+    #[arg(long, help = "Also write a heatmap of the samples taken per pixel")]
+    heatmap: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Png,
+    Ppm,
+    PpmAscii,
+    Exr,
+    ExrFloat,
+    Hdr,
+    Pfm,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ToneMapArg {
+    Clamp,
+    Reinhard,
+    ReinhardExtended,
+    Aces,
+    Hable,
+    Agx,
+}
+
+// Samples per pixel added by each pass when progressive rendering is implied by another flag
+const DEFAULT_PASS_SAMPLES: i32 = 10;
 
 fn main() {
-    // World
-    let mut world = HittableList::new();
-
-    let ground_material = Arc::new(Lambertian::new(Colour::new(0.5, 0.5, 0.5)));
-    world.add(Sphere::new(
-        Point3::new(0.0, -1000.0, 0.0),
-        1000.0,
-        ground_material.clone(),
-    ));
-
-    for a in -11..11 {
-        for b in -11..11 {
-            let choose_mat = random_f64();
-            let centre = Point3::new(
-                a as f64 + 0.9 * random_f64(),
-                0.2,
-                b as f64 + 0.9 * random_f64(),
-            );
-
-            if (centre - Point3::new(4.0, 0.2, 0.0)).length() > 0.9 {
-                let sphere_material: Arc<dyn Material>;
-                if choose_mat < 0.8 {
-                    // diffuse
-                    let albedo = Colour::random() * Colour::random();
-                    sphere_material = Arc::new(Lambertian::new(albedo));
-                    world.add(Sphere::new(centre, 0.2, sphere_material.clone()));
-                } else if choose_mat < 0.95 {
-                    // metal
-                    let albedo = Colour::random_range(0.5, 1.0);
-                    let fuzz = random_f64_in_range(0.0, 0.5);
-                    sphere_material = Arc::new(Metal::new(albedo, fuzz));
-                    world.add(Sphere::new(centre, 0.2, sphere_material.clone()));
-                } else {
-                    // glass
-                    sphere_material = Arc::new(Dielectric::new(1.5));
-                    world.add(Sphere::new(centre, 0.2, sphere_material.clone()));
-                }
-            }
+    let args = Args::parse();
+    if let Err(err) = run(&args) {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+    if let Some(threads) = args.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()?;
+    }
+
+    // Check the output format before spending time on the render
+    let format = match args.format {
+        Some(format) => format.into(),
+        None => ImageFormat::from_path(&args.output).ok_or_else(|| {
+            format!(
+                "unknown image format for {}; use --format",
+                args.output.display()
+            )
+        })?,
+    };
+
+    let scene = match &args.scene {
+        Some(path) => Scene::load(path)?,
+        None => args.preset.unwrap_or(Preset::RandomSpheres).build(),
+    };
+    let mut cam = scene.camera;
+
+    for setting in &args.settings {
+        scene::override_camera(&mut cam, setting)
+            .map_err(|e| format!("--set {}: {}", setting, e))?;
+    }
+    if let Some(width) = args.width {
+        cam.image_width = width;
+    }
+    if let Some(spp) = args.spp {
+        cam.samples_per_pixel = spp;
+    }
+    if let Some(max_depth) = args.max_depth {
+        cam.max_depth = max_depth;
+    }
+    if let Some(seed) = args.seed {
+        cam.seed = seed;
+    }
+
+    let display = DisplayTransform {
+        exposure: args.exposure,
+        tone_map: match args.tone_map {
+            ToneMapArg::Clamp => ToneMap::Clamp,
+            ToneMapArg::Reinhard => ToneMap::Reinhard,
+            ToneMapArg::ReinhardExtended => ToneMap::ReinhardExtended {
+                white_point: args.white_point,
+            },
+            ToneMapArg::Aces => ToneMap::Aces,
+            ToneMapArg::Hable => ToneMap::Hable,
+            ToneMapArg::Agx => ToneMap::Agx,
+        },
+        ..DisplayTransform::default()
+    };
+
+    let world = Arc::new(BvhNode::new(scene.world));
+
+    let progressive =
+        args.progressive.is_some() || args.time_limit.is_some() || args.checkpoint.is_some();
+    if progressive {
+        let mut settings = Progressive::new(args.progressive.unwrap_or(DEFAULT_PASS_SAMPLES));
+        if let Some(seconds) = args.time_limit {
+            settings = settings.with_time_budget(Duration::try_from_secs_f64(seconds)?);
         }
+        if let Some(path) = &args.checkpoint {
+            settings = settings.with_checkpoint(CheckpointSettings {
+                path: path.clone(),
+                interval: Duration::try_from_secs_f64(args.checkpoint_interval)?,
+                scene_fingerprint: scene.fingerprint,
+            });
+        }
+
+        // Overwrite the image after every pass so a partial result can be inspected early
+        let mut result = Ok(());
+        cam.render_progressive(world, &settings, |_, image| {
+            if result.is_ok() {
+                result = output::save(image, &args.output, Some(format), &display);
+            }
+        })?;
+        result.map_err(|e| format!("could not write {}: {}", args.output.display(), e))?;
+    } else {
+        let image = cam.render(world);
+        output::save(&image, &args.output, Some(format), &display)
+            .map_err(|e| format!("could not write {}: {}", args.output.display(), e))?;
     }
 
-    let material1 = Arc::new(Dielectric::new(1.5));
-    world.add(Sphere::new(
-        Point3::new(0.0, 1.0, 0.0),
-        1.0,
-        material1.clone(),
-    ));
-
-    let material2 = Arc::new(Lambertian::new(Colour::new(0.4, 0.2, 0.1)));
-    world.add(Sphere::new(
-        Point3::new(-4.0, 1.0, 0.),
-        1.0,
-        material2.clone(),
-    ));
-
-    let material3 = Arc::new(Metal::new(Colour::new(0.7, 0.6, 0.5), 0.0));
-    world.add(Sphere::new(
-        Point3::new(4.0, 1.0, 0.0),
-        1.0,
-        material3.clone(),
-    ));
-
-    let world = Arc::new(world);
-
-    // Camera
-    let mut cam = Camera::new();
-    cam.aspect_ratio = 16.0 / 9.0;
-    cam.image_width = 1200;
-    cam.samples_per_pixel = 100;
-    cam.max_depth = 50;
-
-    cam.vfov = 20.0;
-    cam.lookfrom = Point3::new(13.0, 2.0, 3.0);
-    cam.lookat = Point3::new(0.0, 0.0, 0.0);
-    cam.vup = Vec3::new(0.0, 1.0, 0.0);
-
-    cam.defocus_angle = 0.6;
-    cam.focus_dist = 10.0;
-
-    cam.render(world);
+    if let Some(path) = &args.heatmap {
+        output::save(
+            &cam.sample_heatmap(),
+            path,
+            None,
+            &DisplayTransform::default(),
+        )
+        .map_err(|e| format!("could not write {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+fn positive_number(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(number) if number > 0.0 && number.is_finite() => Ok(number),
+        Ok(_) => Err("must be a positive number".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+impl From<Format> for ImageFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Png => ImageFormat::Png,
+            Format::Ppm => ImageFormat::Ppm,
+            Format::PpmAscii => ImageFormat::PpmAscii,
+            Format::Exr => ImageFormat::ExrHalf,
+            Format::ExrFloat => ImageFormat::ExrFloat,
+            Format::Hdr => ImageFormat::Hdr,
+            Format::Pfm => ImageFormat::Pfm,
+        }
+    }
 }
@@ -1,8 +1,8 @@
 use crate::colour::Colour;
 use crate::hittable::HitRecord;
-use crate::material::Material;
-use crate::random_f64;
+use crate::material::{Material, ScatterRecord};
 use crate::ray::Ray;
+use crate::sampler::Sampler;
 use crate::vec3::{reflect, refract};
 
 pub struct Dielectric {
@@ -26,10 +26,8 @@ impl Material for Dielectric {
         &self,
         ray: &Ray,
         rec: &HitRecord,
-        attenuation: &mut Colour,
-        scattered: &mut Ray,
-    ) -> bool {
-        *attenuation = Colour::new(1.0, 1.0, 1.0);
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterRecord> {
         let ri = if rec.front_face {
             1.0 / self.refraction_index
         } else {
@@ -43,13 +41,17 @@ impl Material for Dielectric {
         let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
 
         let cannot_refract = ri * sin_theta > 1.0;
-        let direction = if cannot_refract || Dielectric::reflectance(cos_theta, ri) > random_f64() {
-            reflect(unit_direction, rec.normal)
-        } else {
-            refract(unit_direction, rec.normal, ri)
-        };
-
-        *scattered = Ray::new(rec.p, direction);
-        true
+        let direction =
+            if cannot_refract || Dielectric::reflectance(cos_theta, ri) > sampler.next_1d() {
+                reflect(unit_direction, rec.normal)
+            } else {
+                refract(unit_direction, rec.normal, ri)
+            };
+
+        Some(ScatterRecord {
+            attenuation: Colour::new(1.0, 1.0, 1.0),
+            scattered: Ray::with_time(rec.p, direction, ray.time),
+            pdf: None,
+        })
     }
 }
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use crate::colour::Colour;
+use crate::hittable::HitRecord;
+use crate::material::{Material, ScatterRecord};
+use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::texture::Texture;
+use crate::texture::solid::SolidColour;
+
+// Emits light from both faces and absorbs everything that hits it
+pub struct DiffuseLight {
+    tex: Arc<dyn Texture>,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Colour) -> Self {
+        Self::from_texture(Arc::new(SolidColour::new(emit)))
+    }
+
+    pub fn from_texture(tex: Arc<dyn Texture>) -> Self {
+        Self { tex }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(
+        &self,
+        _ray: &Ray,
+        _rec: &HitRecord,
+        _sampler: &mut dyn Sampler,
+    ) -> Option<ScatterRecord> {
+        None
+    }
+
+    fn emitted(&self, rec: &HitRecord) -> Colour {
+        self.tex.value(rec.u, rec.v, rec.p)
+    }
+
+    fn is_emissive(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use crate::PI;
+use crate::colour::Colour;
+use crate::hittable::HitRecord;
+use crate::material::{Material, ScatterRecord};
+use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::texture::Texture;
+use crate::texture::solid::SolidColour;
+use crate::vec3::{Vec3, sample_unit_vector};
+
+// Phase function scattering uniformly in all directions, used inside participating media
+pub struct Isotropic {
+    tex: Arc<dyn Texture>,
+}
+
+impl Isotropic {
+    pub fn new(albedo: Colour) -> Self {
+        Self::from_texture(Arc::new(SolidColour::new(albedo)))
+    }
+
+    pub fn from_texture(tex: Arc<dyn Texture>) -> Self {
+        Self { tex }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        rec: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterRecord> {
+        let (u, v) = sampler.next_2d();
+        Some(ScatterRecord {
+            attenuation: self.tex.value(rec.u, rec.v, rec.p),
+            scattered: Ray::with_time(rec.p, sample_unit_vector(u, v), ray.time),
+            pdf: Some(1.0 / (4.0 * PI)),
+        })
+    }
+
+    // The phase function spreads light evenly over the sphere, with no cosine term
+    fn eval(&self, _ray: &Ray, rec: &HitRecord, _direction: Vec3) -> Colour {
+        self.tex.value(rec.u, rec.v, rec.p) / (4.0 * PI)
+    }
+
+    fn pdf(&self, _ray: &Ray, _rec: &HitRecord, _direction: Vec3) -> f64 {
+        1.0 / (4.0 * PI)
+    }
+}
@@ -1,36 +1,57 @@
+use std::sync::Arc;
+
+use crate::PI;
 use crate::colour::Colour;
 use crate::hittable::HitRecord;
-use crate::material::Material;
+use crate::material::{Material, ScatterRecord};
 use crate::ray::Ray;
-use crate::vec3::random_unit_vector;
+use crate::sampler::Sampler;
+use crate::texture::Texture;
+use crate::texture::solid::SolidColour;
+use crate::vec3::{Vec3, sample_unit_vector};
 
 pub struct Lambertian {
-    albedo: Colour,
+    tex: Arc<dyn Texture>,
 }
 
 impl Lambertian {
     pub fn new(albedo: Colour) -> Self {
-        Self { albedo }
+        Self::from_texture(Arc::new(SolidColour::new(albedo)))
+    }
+
+    pub fn from_texture(tex: Arc<dyn Texture>) -> Self {
+        Self { tex }
     }
 }
 
+// Scatters with a cosine-weighted distribution, so the throughput is just the albedo
 impl Material for Lambertian {
     fn scatter(
         &self,
-        _ray: &Ray,
+        ray: &Ray,
         rec: &HitRecord,
-        attenuation: &mut Colour,
-        scattered: &mut Ray,
-    ) -> bool {
-        let mut scatter_direction = rec.normal + random_unit_vector();
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterRecord> {
+        let (u, v) = sampler.next_2d();
+        let mut scatter_direction = rec.normal + sample_unit_vector(u, v);
 
         // Unlikely scatter direction
         if scatter_direction.near_zero() {
             scatter_direction = rec.normal;
         }
 
-        *scattered = Ray::new(rec.p, scatter_direction);
-        *attenuation = self.albedo;
-        true
+        Some(ScatterRecord {
+            attenuation: self.tex.value(rec.u, rec.v, rec.p),
+            scattered: Ray::with_time(rec.p, scatter_direction, ray.time),
+            pdf: Some(self.pdf(ray, rec, scatter_direction.unit())),
+        })
+    }
+
+    fn eval(&self, ray: &Ray, rec: &HitRecord, direction: Vec3) -> Colour {
+        self.pdf(ray, rec, direction) * self.tex.value(rec.u, rec.v, rec.p)
+    }
+
+    fn pdf(&self, _ray: &Ray, rec: &HitRecord, direction: Vec3) -> f64 {
+        rec.normal.dot(direction).max(0.0) / PI
     }
 }
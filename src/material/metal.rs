@@ -1,32 +1,81 @@
+use std::sync::Arc;
+
+use crate::PI;
 use crate::colour::Colour;
 use crate::hittable::HitRecord;
-use crate::material::Material;
+use crate::material::{Material, ScatterRecord};
 use crate::ray::Ray;
-use crate::vec3::{random_unit_vector, reflect};
+use crate::sampler::Sampler;
+use crate::texture::Texture;
+use crate::texture::solid::SolidColour;
+use crate::vec3::{Vec3, reflect, sample_unit_vector};
 
 pub struct Metal {
-    albedo: Colour,
+    tex: Arc<dyn Texture>,
     fuzz: f64,
 }
 
 impl Metal {
     pub fn new(albedo: Colour, fuzz: f64) -> Self {
-        Self { albedo, fuzz }
+        Self::from_texture(Arc::new(SolidColour::new(albedo)), fuzz)
+    }
+
+    pub fn from_texture(tex: Arc<dyn Texture>, fuzz: f64) -> Self {
+        Self { tex, fuzz }
+    }
+
+    fn reflected(ray: &Ray, rec: &HitRecord) -> Vec3 {
+        reflect(ray.direction, rec.normal).unit()
     }
 }
 
+// Fuzzy reflections perturb the mirror direction r by a uniform point on a sphere of radius
+// `fuzz`. A direction d meets that sphere where |t d - r| = fuzz, at up to two distances t, and
+// each point found contributes t^2 / (4 pi fuzz^2 |cos|) to the density, the cosine being
+// between d and the sphere's normal there.
 impl Material for Metal {
     fn scatter(
         &self,
         ray: &Ray,
         rec: &HitRecord,
-        attenuation: &mut Colour,
-        scattered: &mut Ray,
-    ) -> bool {
-        let mut reflected_direction = reflect(ray.direction, rec.normal);
-        reflected_direction = reflected_direction.unit() + self.fuzz * random_unit_vector();
-        *scattered = Ray::new(rec.p, reflected_direction);
-        *attenuation = self.albedo;
-        true
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterRecord> {
+        let (u, v) = sampler.next_2d();
+        let direction = Self::reflected(ray, rec) + self.fuzz * sample_unit_vector(u, v);
+        let pdf = if self.fuzz > 0.0 {
+            Some(self.pdf(ray, rec, direction.unit()))
+        } else {
+            None
+        };
+        Some(ScatterRecord {
+            attenuation: self.tex.value(rec.u, rec.v, rec.p),
+            scattered: Ray::with_time(rec.p, direction, ray.time),
+            pdf,
+        })
+    }
+
+    fn eval(&self, ray: &Ray, rec: &HitRecord, direction: Vec3) -> Colour {
+        self.pdf(ray, rec, direction) * self.tex.value(rec.u, rec.v, rec.p)
+    }
+
+    fn pdf(&self, ray: &Ray, rec: &HitRecord, direction: Vec3) -> f64 {
+        if self.fuzz <= 0.0 {
+            return 0.0;
+        }
+
+        let b = direction.dot(Self::reflected(ray, rec));
+        let discriminant = b * b - (1.0 - self.fuzz * self.fuzz);
+        if discriminant <= 0.0 {
+            return 0.0;
+        }
+
+        let sqrtd = discriminant.sqrt();
+        let t_squared: f64 = [b - sqrtd, b + sqrtd]
+            .into_iter()
+            .filter(|&t| t > 0.0)
+            .map(|t| t * t)
+            .sum();
+        // |cos| is sqrt(discriminant) / fuzz at both points
+        t_squared / (4.0 * PI * self.fuzz * sqrtd)
     }
 }
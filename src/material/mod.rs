@@ -1,17 +1,50 @@
 use crate::colour::Colour;
 use crate::hittable::HitRecord;
 use crate::ray::Ray;
+use crate::sampler::Sampler;
+use crate::vec3::Vec3;
 
 pub mod dielectric;
+pub mod diffuse_light;
+pub mod isotropic;
 pub mod lambertian;
 pub mod metal;
 
+// A sampled scattering direction
+pub struct ScatterRecord {
+    pub attenuation: Colour, // Throughput along `scattered`, the BSDF times cosine over the pdf
+    pub scattered: Ray,
+    // Density of `scattered`'s direction per unit solid angle. None for specular scattering,
+    // which picks a single direction that `eval` and `pdf` know nothing about.
+    pub pdf: Option<f64>,
+}
+
 pub trait Material: Send + Sync {
+    // None if the ray is absorbed
     fn scatter(
         &self,
         ray: &Ray,
         rec: &HitRecord,
-        attenuation: &mut Colour,
-        scattered: &mut Ray,
-    ) -> bool;
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterRecord>;
+
+    // BSDF times cosine for light arriving from `direction` (a unit vector) and leaving along
+    // the reverse of `ray`. Used to weight light samples; zero for specular materials.
+    fn eval(&self, _ray: &Ray, _rec: &HitRecord, _direction: Vec3) -> Colour {
+        Colour::zero()
+    }
+
+    // Density with which `scatter` picks `direction` (a unit vector)
+    fn pdf(&self, _ray: &Ray, _rec: &HitRecord, _direction: Vec3) -> f64 {
+        0.0
+    }
+
+    fn emitted(&self, _rec: &HitRecord) -> Colour {
+        Colour::zero()
+    }
+
+    // Emissive surfaces are collected into the light list for light sampling
+    fn is_emissive(&self) -> bool {
+        false
+    }
 }
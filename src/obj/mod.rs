@@ -0,0 +1,470 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::hittable::bvh::BvhNode;
+use crate::hittable::mesh::TriangleMesh;
+use crate::hittable::{HitRecord, Hittable, HittableList};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vec3::{Point3, Vec3};
+
+pub mod mtl;
+
+#[derive(Debug)]
+pub enum ObjError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::Io { path, source } => {
+                write!(f, "could not read {}: {}", path.display(), source)
+            }
+            ObjError::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+        }
+    }
+}
+
+impl Error for ObjError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ObjError::Io { source, .. } => Some(source),
+            ObjError::Parse { .. } => None,
+        }
+    }
+}
+
+// A named part of the model, one per group/object and material combination
+pub struct ObjGroup {
+    pub name: String,
+    pub material: Option<String>,
+    pub mesh: Arc<TriangleMesh>,
+}
+
+pub struct ObjModel {
+    groups: Vec<ObjGroup>,
+    bvh: BvhNode,
+    sources: Vec<PathBuf>,
+}
+
+// Index of a face corner into the position, texture coordinate and normal arrays
+type VertexKey = (usize, Option<usize>, Option<usize>);
+
+struct GroupBuilder {
+    name: String,
+    material: Option<String>,
+    vertex_map: HashMap<VertexKey, usize>,
+    vertices: Vec<Point3>,
+    uvs: Vec<Option<(f64, f64)>>,
+    normals: Vec<Option<Vec3>>,
+    indices: Vec<[usize; 3]>,
+}
+
+impl GroupBuilder {
+    fn vertex(&mut self, key: VertexKey, data: &ObjData) -> usize {
+        *self.vertex_map.entry(key).or_insert_with(|| {
+            let (v, vt, vn) = key;
+            self.vertices.push(data.positions[v]);
+            self.uvs.push(vt.map(|i| data.texcoords[i]));
+            self.normals.push(vn.map(|i| data.normals[i]));
+            self.vertices.len() - 1
+        })
+    }
+}
+
+#[derive(Default)]
+struct ObjData {
+    positions: Vec<Point3>,
+    texcoords: Vec<(f64, f64)>,
+    normals: Vec<Vec3>,
+}
+
+impl ObjModel {
+    // Loads an OBJ file and the MTL libraries it references. Faces without a material use
+    // default_material.
+    pub fn load<P: AsRef<Path>>(
+        path: P,
+        default_material: Arc<dyn Material>,
+    ) -> Result<Self, ObjError> {
+        let path = path.as_ref();
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+
+        let mut data = ObjData::default();
+        let mut materials = HashMap::new();
+        let mut builders: Vec<GroupBuilder> = vec![];
+        let mut builder_lookup: HashMap<(String, Option<String>), usize> = HashMap::new();
+        let mut object_name: Option<String> = None;
+        let mut group_name: Option<String> = None;
+        let mut material_name: Option<String> = None;
+        let mut sources = vec![path.to_path_buf()];
+
+        for (line_number, line) in read_lines(path)? {
+            let parse_error = |message: String| ObjError::Parse {
+                path: path.to_path_buf(),
+                line: line_number,
+                message,
+            };
+
+            let mut tokens = line.split_whitespace();
+            let Some(keyword) = tokens.next() else {
+                continue;
+            };
+            let args: Vec<&str> = tokens.collect();
+
+            match keyword {
+                "v" | "vn" => {
+                    let values = parse_floats(&args).map_err(&parse_error)?;
+                    let &[x, y, z, ..] = values.as_slice() else {
+                        return Err(parse_error(format!("'{}' needs 3 coordinates", keyword)));
+                    };
+                    if keyword == "v" {
+                        data.positions.push(Point3::new(x, y, z));
+                    } else {
+                        let normal = Vec3::new(x, y, z);
+                        if normal.near_zero() {
+                            return Err(parse_error("'vn' needs a non-zero normal".to_string()));
+                        }
+                        data.normals.push(normal.unit());
+                    }
+                }
+                "vt" => {
+                    let values = parse_floats(&args).map_err(&parse_error)?;
+                    match values[..] {
+                        [u] => data.texcoords.push((u, 0.0)),
+                        [u, v, ..] => data.texcoords.push((u, v)),
+                        _ => return Err(parse_error("'vt' needs a coordinate".to_string())),
+                    }
+                }
+                "f" => {
+                    if args.len() < 3 {
+                        return Err(parse_error(format!(
+                            "face needs at least 3 vertices, found {}",
+                            args.len()
+                        )));
+                    }
+                    let keys = args
+                        .iter()
+                        .map(|arg| parse_face_vertex(arg, &data))
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(&parse_error)?;
+
+                    let name = match (&object_name, &group_name) {
+                        (Some(o), Some(g)) => format!("{}/{}", o, g),
+                        (Some(name), None) | (None, Some(name)) => name.clone(),
+                        (None, None) => "default".to_string(),
+                    };
+                    let lookup_key = (name.clone(), material_name.clone());
+                    let index = *builder_lookup.entry(lookup_key).or_insert_with(|| {
+                        builders.push(GroupBuilder {
+                            name,
+                            material: material_name.clone(),
+                            vertex_map: HashMap::new(),
+                            vertices: vec![],
+                            uvs: vec![],
+                            normals: vec![],
+                            indices: vec![],
+                        });
+                        builders.len() - 1
+                    });
+                    let builder = &mut builders[index];
+
+                    // Triangulate the polygon as a fan around its first vertex
+                    let first = builder.vertex(keys[0], &data);
+                    for pair in keys[1..].windows(2) {
+                        let i1 = builder.vertex(pair[0], &data);
+                        let i2 = builder.vertex(pair[1], &data);
+                        builder.indices.push([first, i1, i2]);
+                    }
+                }
+                "o" => object_name = Some(args.join(" ")),
+                "g" => group_name = (!args.is_empty()).then(|| args.join(" ")),
+                "usemtl" => {
+                    let name = args.join(" ");
+                    if !materials.contains_key(&name) {
+                        return Err(parse_error(format!("undefined material '{}'", name)));
+                    }
+                    material_name = Some(name);
+                }
+                "mtllib" => {
+                    for file in args {
+                        let mtl_path = base_dir.join(file);
+                        let library = mtl::load_mtl(&mtl_path)?;
+                        sources.push(mtl_path);
+                        sources.extend(library.values().filter_map(|m| m.map_kd_path.clone()));
+                        materials.extend(library);
+                    }
+                }
+                // Smoothing groups, lines, points and free-form geometry are ignored
+                _ => {}
+            }
+        }
+
+        let mut converted: HashMap<String, Arc<dyn Material>> = HashMap::new();
+        let mut groups = vec![];
+        for builder in builders {
+            let mat = match &builder.material {
+                Some(name) => converted
+                    .entry(name.clone())
+                    .or_insert_with(|| materials[name].to_material())
+                    .clone(),
+                None => default_material.clone(),
+            };
+            // Attributes are only used when every vertex of the group provides them
+            let uvs: Option<Vec<_>> = builder.uvs.into_iter().collect();
+            let normals: Option<Vec<_>> = builder.normals.into_iter().collect();
+            let mesh =
+                TriangleMesh::with_attributes(builder.vertices, normals, uvs, builder.indices, mat);
+            groups.push(ObjGroup {
+                name: builder.name,
+                material: builder.material,
+                mesh: Arc::new(mesh),
+            });
+        }
+
+        let bvh = BvhNode::from_objects(
+            groups
+                .iter()
+                .map(|group| group.mesh.clone() as Arc<dyn Hittable>)
+                .collect(),
+        );
+        // Texture maps come out of a HashMap, so put the list in a stable order
+        sources.sort();
+        sources.dedup();
+        Ok(Self {
+            groups,
+            bvh,
+            sources,
+        })
+    }
+
+    pub fn groups(&self) -> &[ObjGroup] {
+        &self.groups
+    }
+
+    // Files read to build the model: the OBJ file, its MTL libraries and their texture maps
+    pub fn sources(&self) -> &[PathBuf] {
+        &self.sources
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.groups.iter().map(|g| g.mesh.triangle_count()).sum()
+    }
+}
+
+impl Hittable for ObjModel {
+    fn hit(&self, ray: &Ray, t_range: Interval, rec: &mut HitRecord) -> bool {
+        self.bvh.hit(ray, t_range, rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bvh.bounding_box()
+    }
+
+    fn collect_lights(&self, lights: &mut HittableList) {
+        self.bvh.collect_lights(lights);
+    }
+}
+
+// Parses a face corner of the form v, v/vt, v//vn or v/vt/vn
+fn parse_face_vertex(arg: &str, data: &ObjData) -> Result<VertexKey, String> {
+    let mut parts = arg.split('/');
+    let v = resolve_index(parts.next(), data.positions.len(), "vertex", arg)?
+        .ok_or_else(|| format!("face vertex '{}' has no position index", arg))?;
+    let vt = resolve_index(
+        parts.next(),
+        data.texcoords.len(),
+        "texture coordinate",
+        arg,
+    )?;
+    let vn = resolve_index(parts.next(), data.normals.len(), "normal", arg)?;
+    if parts.next().is_some() {
+        return Err(format!("malformed face vertex '{}'", arg));
+    }
+    Ok((v, vt, vn))
+}
+
+// OBJ indices are 1-based, negative values count back from the latest element
+fn resolve_index(
+    token: Option<&str>,
+    count: usize,
+    kind: &str,
+    arg: &str,
+) -> Result<Option<usize>, String> {
+    let Some(token) = token.filter(|t| !t.is_empty()) else {
+        return Ok(None);
+    };
+    let index: i64 = token
+        .parse()
+        .map_err(|_| format!("invalid {} index '{}' in '{}'", kind, token, arg))?;
+    let resolved = match index {
+        i if i > 0 => i - 1,
+        i if i < 0 => count as i64 + i,
+        _ => {
+            return Err(format!(
+                "{} index 0 in '{}' (indices start at 1)",
+                kind, arg
+            ));
+        }
+    };
+    if resolved < 0 || resolved >= count as i64 {
+        return Err(format!(
+            "{} index {} in '{}' out of range ({} defined)",
+            kind, index, arg, count
+        ));
+    }
+    Ok(Some(resolved as usize))
+}
+
+pub(crate) fn parse_floats(args: &[&str]) -> Result<Vec<f64>, String> {
+    args.iter()
+        .map(|arg| {
+            arg.parse::<f64>()
+                .map_err(|_| format!("invalid number '{}'", arg))
+        })
+        .collect()
+}
+
+// Reads a file into (line number, content) pairs with comments stripped and backslash
+// continuations joined onto the line they started on
+pub(crate) fn read_lines(path: &Path) -> Result<Vec<(usize, String)>, ObjError> {
+    let contents = fs::read_to_string(path).map_err(|source| ObjError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    let mut lines = vec![];
+    let mut pending: Option<(usize, String)> = None;
+    for (index, raw) in contents.lines().enumerate() {
+        let content = raw.split('#').next().unwrap_or("");
+        let (number, mut line) = pending.take().unwrap_or((index + 1, String::new()));
+        match content.trim_end().strip_suffix('\\') {
+            Some(continued) => {
+                line.push_str(continued);
+                line.push(' ');
+                pending = Some((number, line));
+            }
+            None => {
+                line.push_str(content);
+                lines.push((number, line));
+            }
+        }
+    }
+    lines.extend(pending);
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colour::Colour;
+    use crate::material::lambertian::Lambertian;
+
+    // Writes `files` into a fresh directory and returns the path of the first one
+    fn write_files(test: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("raytracer-obj-{}-{}", test, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for (name, contents) in files {
+            fs::write(dir.join(name), contents).unwrap();
+        }
+        dir.join(files[0].0)
+    }
+
+    fn load(test: &str, files: &[(&str, &str)]) -> Result<ObjModel, ObjError> {
+        let grey = Arc::new(Lambertian::new(Colour::new(0.5, 0.5, 0.5)));
+        ObjModel::load(write_files(test, files), grey)
+    }
+
+    fn error_line(result: Result<ObjModel, ObjError>) -> usize {
+        match result {
+            Err(ObjError::Parse { line, .. }) => line,
+            Err(e) => panic!("expected a parse error, got {}", e),
+            Ok(_) => panic!("expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn triangulates_polygons_per_group() {
+        let obj = "\
+# two groups
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 0 0 1
+g top
+f 1 2 3 4
+g side
+f -5 -4 -1
+";
+        let model = load("groups", &[("model.obj", obj)]).unwrap();
+        assert_eq!(model.triangle_count(), 3);
+        let names: Vec<&str> = model.groups().iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, ["top", "side"]);
+    }
+
+    #[test]
+    fn assigns_mtl_materials_to_groups() {
+        let obj = "mtllib model.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl shiny\nf 1 2 3\n";
+        let mtl = "newmtl shiny\nKd 0.8 0.2 0.2\nKs 0.9 0.9 0.9\nillum 3\n";
+        let model = load("mtl", &[("model.obj", obj), ("model.mtl", mtl)]).unwrap();
+        assert_eq!(model.groups().len(), 1);
+        assert_eq!(model.groups()[0].material.as_deref(), Some("shiny"));
+
+        let materials = mtl::load_mtl(&write_files("mtl", &[("model.mtl", mtl)])).unwrap();
+        let shiny = &materials["shiny"];
+        assert_eq!(shiny.illum, 3);
+        assert!((shiny.kd - Colour::new(0.8, 0.2, 0.2)).near_zero());
+    }
+
+    #[test]
+    fn parse_errors_report_the_line() {
+        let bad_index = "v 0 0 0\nv 1 0 0\n\nf 1 2 5\n";
+        assert_eq!(error_line(load("index", &[("a.obj", bad_index)])), 4);
+
+        let bad_number = "v 0 0 0\nv 1 zero 0\n";
+        assert_eq!(error_line(load("number", &[("a.obj", bad_number)])), 2);
+
+        let zero_index = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 0 1 2\n";
+        assert_eq!(error_line(load("zero", &[("a.obj", zero_index)])), 4);
+
+        let short_face = "v 0 0 0\nv 1 0 0\nf 1 2\n";
+        assert_eq!(error_line(load("short", &[("a.obj", short_face)])), 3);
+
+        let zero_normal = "v 0 0 0\nvn 0 0 1\nvn 0 0 0\n";
+        assert_eq!(error_line(load("normal", &[("a.obj", zero_normal)])), 3);
+    }
+
+    #[test]
+    fn mtl_errors_name_the_mtl_file() {
+        let obj = "mtllib bad.mtl\n";
+        let mtl = "newmtl red\nKd 1 0\n";
+        match load("badmtl", &[("a.obj", obj), ("bad.mtl", mtl)]) {
+            Err(ObjError::Parse { path, line, .. }) => {
+                assert!(path.ends_with("bad.mtl"));
+                assert_eq!(line, 2);
+            }
+            _ => panic!("expected a parse error in the MTL file"),
+        }
+    }
+}
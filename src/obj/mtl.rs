@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::colour::Colour;
+use crate::material::Material;
+use crate::material::dielectric::Dielectric;
+use crate::material::lambertian::Lambertian;
+use crate::material::metal::Metal;
+use crate::obj::{ObjError, parse_floats, read_lines};
+use crate::texture::Texture;
+use crate::texture::image::ImageTexture;
+use crate::texture::solid::SolidColour;
+
+// Material statements from an MTL file that the renderer understands
+#[derive(Clone)]
+pub struct MtlMaterial {
+    pub name: String,
+    pub kd: Colour,                        // Diffuse colour
+    pub ks: Colour,                        // Specular colour
+    pub ns: f64,                           // Specular exponent
+    pub ni: Option<f64>,                   // Index of refraction
+    pub d: f64,                            // Dissolve (1 is opaque)
+    pub illum: u32,                        // Illumination model
+    pub map_kd: Option<Arc<ImageTexture>>, // Diffuse texture map
+    pub map_kd_path: Option<PathBuf>,      // File the diffuse texture map was loaded from
+}
+
+impl MtlMaterial {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kd: Colour::new(0.8, 0.8, 0.8),
+            ks: Colour::zero(),
+            ns: 0.0,
+            ni: None,
+            d: 1.0,
+            illum: 2,
+            map_kd: None,
+            map_kd_path: None,
+        }
+    }
+
+    // Maps the MTL parameters onto the closest renderer material:
+    // - illum 4, 6, 7 or 9, or d < 1: Dielectric with index Ni (1.5 if unset)
+    // - illum 3, 5 or 8: Metal tinted by Ks (Kd/map_Kd if Ks is black), fuzz derived from Ns
+    // - anything else: Lambertian with albedo map_Kd if present, Kd otherwise
+    pub fn to_material(&self) -> Arc<dyn Material> {
+        if matches!(self.illum, 4 | 6 | 7 | 9) || self.d < 1.0 {
+            return Arc::new(Dielectric::new(self.ni.unwrap_or(1.5)));
+        }
+        if matches!(self.illum, 3 | 5 | 8) {
+            // Roughly match the lobe width of a Phong exponent
+            let fuzz = (2.0 / (self.ns + 2.0)).sqrt().min(1.0);
+            if !self.ks.near_zero() {
+                return Arc::new(Metal::new(self.ks, fuzz));
+            }
+            return Arc::new(Metal::from_texture(self.diffuse_texture(), fuzz));
+        }
+        Arc::new(Lambertian::from_texture(self.diffuse_texture()))
+    }
+
+    fn diffuse_texture(&self) -> Arc<dyn Texture> {
+        match &self.map_kd {
+            Some(map) => map.clone(),
+            None => Arc::new(SolidColour::new(self.kd)),
+        }
+    }
+}
+
+pub fn load_mtl(path: &Path) -> Result<HashMap<String, MtlMaterial>, ObjError> {
+    let mut materials = HashMap::new();
+    let mut current: Option<MtlMaterial> = None;
+
+    for (line_number, line) in read_lines(path)? {
+        let parse_error = |message: String| ObjError::Parse {
+            path: path.to_path_buf(),
+            line: line_number,
+            message,
+        };
+
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        if keyword == "newmtl" {
+            if args.is_empty() {
+                return Err(parse_error("newmtl without a material name".to_string()));
+            }
+            if let Some(mat) = current.take() {
+                materials.insert(mat.name.clone(), mat);
+            }
+            current = Some(MtlMaterial::new(&args.join(" ")));
+            continue;
+        }
+
+        let Some(mat) = current.as_mut() else {
+            if matches!(keyword, "Kd" | "Ks" | "Ns" | "Ni" | "d" | "Tr" | "illum") {
+                return Err(parse_error(format!("'{}' before any newmtl", keyword)));
+            }
+            continue;
+        };
+
+        match keyword {
+            "Kd" | "Ks" => {
+                let values = parse_floats(&args).map_err(&parse_error)?;
+                let colour = match values[..] {
+                    [r] => Colour::new(r, r, r),
+                    [r, g, b, ..] => Colour::new(r, g, b),
+                    _ => return Err(parse_error(format!("'{}' needs 1 or 3 values", keyword))),
+                };
+                if keyword == "Kd" {
+                    mat.kd = colour;
+                } else {
+                    mat.ks = colour;
+                }
+            }
+            "Ns" | "Ni" | "d" | "Tr" => {
+                let values = parse_floats(&args).map_err(&parse_error)?;
+                let &[value, ..] = values.as_slice() else {
+                    return Err(parse_error(format!("'{}' needs a value", keyword)));
+                };
+                match keyword {
+                    "Ns" => mat.ns = value,
+                    "Ni" => mat.ni = Some(value),
+                    "d" => mat.d = value,
+                    _ => mat.d = 1.0 - value,
+                }
+            }
+            "illum" => {
+                mat.illum = args
+                    .first()
+                    .and_then(|arg| arg.parse().ok())
+                    .ok_or_else(|| parse_error("illum needs an integer model".to_string()))?;
+            }
+            "map_Kd" => {
+                // Options such as -s or -o are not supported, the file name comes last
+                let Some(file) = args.last() else {
+                    return Err(parse_error("map_Kd needs a file name".to_string()));
+                };
+                let texture_path = path.parent().unwrap_or(Path::new("")).join(file);
+                let texture = ImageTexture::load(&texture_path).map_err(|err| {
+                    parse_error(format!(
+                        "could not load texture {}: {}",
+                        texture_path.display(),
+                        err
+                    ))
+                })?;
+                mat.map_kd = Some(Arc::new(texture));
+                mat.map_kd_path = Some(texture_path);
+            }
+            // Other texture maps and statements are not supported
+            _ => {}
+        }
+    }
+
+    if let Some(mat) = current.take() {
+        materials.insert(mat.name.clone(), mat);
+    }
+    Ok(materials)
+}
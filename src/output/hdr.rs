@@ -0,0 +1,153 @@
+use std::io::{self, Write};
+
+use crate::colour::Colour;
+use crate::framebuffer::Framebuffer;
+
+// Radiance RGBE (.hdr) with run length encoded scanlines
+pub fn write_hdr<W: Write>(out: &mut W, image: &Framebuffer) -> io::Result<()> {
+    let (width, height) = (image.width(), image.height());
+    write!(
+        out,
+        "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+        height, width
+    )?;
+
+    // Readers only accept run length encoding for these widths
+    let use_rle = (8..0x8000).contains(&width);
+
+    let mut components: [Vec<u8>; 4] = Default::default();
+    for row in image.rows() {
+        let rgbe: Vec<[u8; 4]> = row.iter().map(|&p| to_rgbe(p)).collect();
+        if !use_rle {
+            for pixel in rgbe {
+                out.write_all(&pixel)?;
+            }
+            continue;
+        }
+
+        out.write_all(&[2, 2, (width >> 8) as u8, (width & 0xff) as u8])?;
+        for (c, component) in components.iter_mut().enumerate() {
+            component.clear();
+            component.extend(rgbe.iter().map(|pixel| pixel[c]));
+            write_rle(out, component)?;
+        }
+    }
+    Ok(())
+}
+
+fn to_rgbe(colour: Colour) -> [u8; 4] {
+    let r = colour.x.max(0.0);
+    let g = colour.y.max(0.0);
+    let b = colour.z.max(0.0);
+    let v = r.max(g).max(b);
+    if v < 1e-32 || !v.is_finite() {
+        return [0, 0, 0, 0];
+    }
+
+    // v = m * 2^e with m in [0.5, 1)
+    let mut e = v.log2().floor() as i32 + 1;
+    let mut m = v / 2f64.powi(e);
+    if m >= 1.0 {
+        m *= 0.5;
+        e += 1;
+    }
+    let scale = m * 256.0 / v;
+    [
+        (r * scale) as u8,
+        (g * scale) as u8,
+        (b * scale) as u8,
+        (e + 128).clamp(0, 255) as u8,
+    ]
+}
+
+// Runs of 3 or more equal bytes are written as (128 + count, value), everything else as
+// literal dumps of (count, bytes...)
+fn write_rle<W: Write>(out: &mut W, data: &[u8]) -> io::Result<()> {
+    const MIN_RUN: usize = 3;
+    const MAX_COUNT: usize = 127;
+
+    let mut i = 0;
+    while i < data.len() {
+        // Look for the next run
+        let mut run_start = i;
+        let mut run_length = 0;
+        while run_start < data.len() {
+            run_length = data[run_start..]
+                .iter()
+                .take(MAX_COUNT)
+                .take_while(|&&b| b == data[run_start])
+                .count();
+            if run_length >= MIN_RUN {
+                break;
+            }
+            run_start += run_length;
+            run_length = 0;
+        }
+
+        // Literal bytes before the run
+        while i < run_start {
+            let count = (run_start - i).min(MAX_COUNT + 1);
+            out.write_all(&[count as u8])?;
+            out.write_all(&data[i..i + count])?;
+            i += count;
+        }
+
+        if run_length >= MIN_RUN {
+            out.write_all(&[128 + run_length as u8, data[run_start]])?;
+            i = run_start + run_length;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_rle(mut data: &[u8]) -> Vec<u8> {
+        let mut decoded = Vec::new();
+        while let [count, rest @ ..] = data {
+            if *count > 128 {
+                let run = (*count - 128) as usize;
+                decoded.extend(std::iter::repeat_n(rest[0], run));
+                data = &rest[1..];
+            } else {
+                let count = *count as usize;
+                assert!(count > 0, "zero length dump");
+                decoded.extend_from_slice(&rest[..count]);
+                data = &rest[count..];
+            }
+        }
+        decoded
+    }
+
+    fn encode(data: &[u8]) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        write_rle(&mut encoded, data).unwrap();
+        encoded
+    }
+
+    #[test]
+    fn encodes_runs_and_literals() {
+        assert_eq!(encode(&[1, 2, 7, 7, 7, 7, 3]), [2, 1, 2, 132, 7, 1, 3]);
+        assert_eq!(encode(&[5, 5, 6]), [3, 5, 5, 6]);
+        assert!(encode(&[]).is_empty());
+    }
+
+    #[test]
+    fn round_trips_long_runs_and_literals() {
+        let mut data = vec![9; 300];
+        data.extend((0..=255).map(|b| b as u8));
+        data.extend([4, 4, 4, 1, 1]);
+        data.extend((0..200).map(|i| (i % 2) as u8));
+        let encoded = encode(&data);
+        assert!(encoded.len() < data.len());
+        assert_eq!(read_rle(&encoded), data);
+    }
+
+    #[test]
+    fn writes_rgbe_mantissa_and_exponent() {
+        assert_eq!(to_rgbe(Colour::new(1.0, 0.5, 0.0)), [128, 64, 0, 129]);
+        assert_eq!(to_rgbe(Colour::zero()), [0, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,87 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::display::DisplayTransform;
+use crate::framebuffer::Framebuffer;
+
+pub mod exr;
+pub mod hdr;
+pub mod pfm;
+pub mod png;
+pub mod ppm;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,      // 8-bit RGB PNG
+    Ppm,      // Binary P6 PPM
+    PpmAscii, // Plain text P3 PPM
+    ExrHalf,  // OpenEXR with 16-bit float channels
+    ExrFloat, // OpenEXR with 32-bit float channels
+    Hdr,      // Radiance RGBE
+    Pfm,      // Portable float map
+}
+
+impl ImageFormat {
+    // Infers the format from a file extension: .png, .ppm (binary), .exr (half floats), .hdr
+    // or .pfm
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "png" => Some(ImageFormat::Png),
+            "ppm" => Some(ImageFormat::Ppm),
+            "exr" => Some(ImageFormat::ExrHalf),
+            "hdr" => Some(ImageFormat::Hdr),
+            "pfm" => Some(ImageFormat::Pfm),
+            _ => None,
+        }
+    }
+
+    // Float formats store the linear radiance without clamping or gamma encoding
+    pub fn is_hdr(&self) -> bool {
+        matches!(
+            self,
+            ImageFormat::ExrHalf | ImageFormat::ExrFloat | ImageFormat::Hdr | ImageFormat::Pfm
+        )
+    }
+}
+
+// Writes an image to a file. The format is inferred from the extension unless given explicitly.
+// 8-bit formats pass through the display transform, float formats store linear radiance.
+pub fn save<P: AsRef<Path>>(
+    image: &Framebuffer,
+    path: P,
+    format: Option<ImageFormat>,
+    display: &DisplayTransform,
+) -> io::Result<()> {
+    let path = path.as_ref();
+    let format = format
+        .or_else(|| ImageFormat::from_path(path))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unknown image format for {}", path.display()),
+            )
+        })?;
+
+    let mut out = BufWriter::new(File::create(path)?);
+    write(&mut out, image, format, display)?;
+    out.flush()
+}
+
+pub fn write<W: Write>(
+    out: &mut W,
+    image: &Framebuffer,
+    format: ImageFormat,
+    display: &DisplayTransform,
+) -> io::Result<()> {
+    match format {
+        ImageFormat::Png => png::write_png(out, image, display),
+        ImageFormat::Ppm => ppm::write_ppm(out, image, display),
+        ImageFormat::PpmAscii => ppm::write_ppm_ascii(out, image, display),
+        ImageFormat::ExrHalf => exr::write_exr(out, image, true),
+        ImageFormat::ExrFloat => exr::write_exr(out, image, false),
+        ImageFormat::Hdr => hdr::write_hdr(out, image),
+        ImageFormat::Pfm => pfm::write_pfm(out, image),
+    }
+}
@@ -0,0 +1,17 @@
+use std::io::{self, Write};
+
+use crate::framebuffer::Framebuffer;
+
+// Portable float map, little-endian RGB stored bottom row first
+pub fn write_pfm<W: Write>(out: &mut W, image: &Framebuffer) -> io::Result<()> {
+    // A negative scale marks the data as little-endian
+    write!(out, "PF\n{} {}\n-1.0\n", image.width(), image.height())?;
+    for row in image.rows().rev() {
+        for pixel in row {
+            for value in [pixel.x, pixel.y, pixel.z] {
+                out.write_all(&(value as f32).to_le_bytes())?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use std::io::{self, Write};
+
+use image::codecs::png::PngEncoder;
+use image::{ExtendedColorType, ImageEncoder};
+
+use crate::display::DisplayTransform;
+use crate::framebuffer::Framebuffer;
+
+// 8-bit RGB PNG, encoded for display with the given transform
+pub fn write_png<W: Write>(
+    out: &mut W,
+    image: &Framebuffer,
+    display: &DisplayTransform,
+) -> io::Result<()> {
+    let bytes: Vec<u8> = image
+        .pixels()
+        .iter()
+        .flat_map(|&p| display.to_bytes(p))
+        .collect();
+    PngEncoder::new(out)
+        .write_image(
+            &bytes,
+            image.width() as u32,
+            image.height() as u32,
+            ExtendedColorType::Rgb8,
+        )
+        .map_err(io::Error::other)
+}
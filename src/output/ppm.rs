@@ -0,0 +1,31 @@
+use std::io::{self, Write};
+
+use crate::display::DisplayTransform;
+use crate::framebuffer::Framebuffer;
+
+// Binary (P6) PPM
+pub fn write_ppm<W: Write>(
+    out: &mut W,
+    image: &Framebuffer,
+    display: &DisplayTransform,
+) -> io::Result<()> {
+    write!(out, "P6\n{} {}\n255\n", image.width(), image.height())?;
+    for &pixel_colour in image.pixels() {
+        out.write_all(&display.to_bytes(pixel_colour))?;
+    }
+    Ok(())
+}
+
+// Plain text (P3) PPM
+pub fn write_ppm_ascii<W: Write>(
+    out: &mut W,
+    image: &Framebuffer,
+    display: &DisplayTransform,
+) -> io::Result<()> {
+    write!(out, "P3\n{} {}\n255\n", image.width(), image.height())?;
+    for &pixel_colour in image.pixels() {
+        let [r, g, b] = display.to_bytes(pixel_colour);
+        writeln!(out, "{} {} {}", r, g, b)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::colour::Colour;
+use crate::framebuffer::Framebuffer;
+use crate::tile::Tile;
+
+// Receives render progress. A render is made of one or more passes over the image (a single
+// pass normally, one per round or pass when rendering adaptively or progressively), each split
+// into tiles. `tile_done` is called from the render threads as tiles finish, so
+// implementations must be thread safe.
+pub trait Progress: Send + Sync {
+    // Pass `pass` (starting at 1) is about to render `tiles` tiles
+    fn begin_pass(&self, _pass: usize, _tiles: usize) {}
+
+    // A tile has finished; `pixels` holds its current estimate, row by row
+    fn tile_done(&self, _tile: &Tile, _pixels: &[Colour]) {}
+
+    // The pass has finished and `image` holds the estimate so far
+    fn end_pass(&self, _pass: usize, _image: &Framebuffer) {}
+}
+
+impl fmt::Debug for dyn Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("dyn Progress")
+    }
+}
+
+// Prints a tile count and estimated time remaining to stderr
+#[derive(Debug, Default)]
+pub struct StderrProgress {
+    state: Mutex<PassState>,
+}
+
+#[derive(Debug, Default)]
+struct PassState {
+    pass: usize,
+    tiles: usize,
+    done: usize,
+    start: Option<Instant>,
+}
+
+impl StderrProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Progress for StderrProgress {
+    fn begin_pass(&self, pass: usize, tiles: usize) {
+        *self.state.lock().unwrap() = PassState {
+            pass,
+            tiles,
+            done: 0,
+            start: Some(Instant::now()),
+        };
+    }
+
+    fn tile_done(&self, _tile: &Tile, _pixels: &[Colour]) {
+        let mut state = self.state.lock().unwrap();
+        state.done += 1;
+        let elapsed = state.start.map_or(Duration::ZERO, |start| start.elapsed());
+        let remaining = elapsed.mul_f64((state.tiles - state.done) as f64 / state.done as f64);
+        eprint!(
+            "\rPass {}: {}/{} tiles, {:.0?} remaining   ",
+            state.pass, state.done, state.tiles, remaining
+        );
+    }
+
+    fn end_pass(&self, pass: usize, _image: &Framebuffer) {
+        let state = self.state.lock().unwrap();
+        let elapsed = state.start.map_or(Duration::ZERO, |start| start.elapsed());
+        eprintln!(
+            "\rPass {}: done in {:.1?}                    ",
+            pass, elapsed
+        );
+    }
+}
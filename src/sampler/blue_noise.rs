@@ -0,0 +1,163 @@
+use std::sync::OnceLock;
+
+use crate::rng::{Pcg32, mix64};
+use crate::sampler::sobol::scrambled_sobol;
+use crate::sampler::{Sampler, bits_to_unit};
+
+pub const TILE_SIZE: usize = 64;
+
+const TILE_SEED: u64 = 0xb1e5;
+const SIGMA: f64 = 1.5;
+
+// Every pixel uses the same scrambled Sobol points, rotated by a value read from a blue-noise
+// tile (Georgiev & Fajardo, "Blue-noise Dithered Sampling"). Neighbouring pixels get very
+// different offsets, so the remaining error looks like fine-grained blue noise rather than
+// clumps, and it stays low-discrepancy within each pixel.
+pub struct BlueNoiseSampler {
+    seed: u64,
+    tile: &'static [f64],
+    x: usize,
+    y: usize,
+    index: u32,
+    dimension: u64,
+}
+
+impl BlueNoiseSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            tile: tile(),
+            x: 0,
+            y: 0,
+            index: 0,
+            dimension: 0,
+        }
+    }
+
+    fn next_dimension_seed(&mut self) -> u64 {
+        self.dimension += 1;
+        mix64(self.seed ^ mix64(self.dimension))
+    }
+
+    // Tile value at this pixel, with the tile shifted by a per-dimension amount so dimensions
+    // don't share offsets
+    fn offset(&self, shift: u64) -> f64 {
+        let dx = shift as usize % TILE_SIZE;
+        let dy = (shift >> 32) as usize % TILE_SIZE;
+        self.tile[((self.y + dy) % TILE_SIZE) * TILE_SIZE + (self.x + dx) % TILE_SIZE]
+    }
+}
+
+impl Sampler for BlueNoiseSampler {
+    fn start_sample(&mut self, x: u32, y: u32, index: u32) {
+        self.x = x as usize;
+        self.y = y as usize;
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    fn next_1d(&mut self) -> f64 {
+        let seed = self.next_dimension_seed();
+        let [x, _] = scrambled_sobol(self.index, seed);
+        (bits_to_unit(x) + self.offset(mix64(seed))).fract()
+    }
+
+    fn next_2d(&mut self) -> (f64, f64) {
+        let seed = self.next_dimension_seed();
+        let [x, y] = scrambled_sobol(self.index, seed);
+        let shift = mix64(seed);
+        (
+            (bits_to_unit(x) + self.offset(shift)).fract(),
+            (bits_to_unit(y) + self.offset(mix64(shift))).fract(),
+        )
+    }
+}
+
+// Shared tile, built on first use
+pub fn tile() -> &'static [f64] {
+    static TILE: OnceLock<Vec<f64>> = OnceLock::new();
+    TILE.get_or_init(|| void_and_cluster(TILE_SIZE, TILE_SEED))
+}
+
+// Blue-noise threshold map of `size` x `size` values in (0, 1), tiling seamlessly, built with
+// Ulichney's void-and-cluster method
+pub fn void_and_cluster(size: usize, seed: u64) -> Vec<f64> {
+    let n = size * size;
+
+    // Gaussian energy filter on the torus, indexed by offset
+    let mut kernel = vec![0.0; n];
+    for dy in 0..size {
+        for dx in 0..size {
+            let wx = dx.min(size - dx) as f64;
+            let wy = dy.min(size - dy) as f64;
+            kernel[dy * size + dx] = (-(wx * wx + wy * wy) / (2.0 * SIGMA * SIGMA)).exp();
+        }
+    }
+    let splat = |energy: &mut [f64], cell: usize, sign: f64| {
+        let (cx, cy) = (cell % size, cell / size);
+        for y in 0..size {
+            let row = ((y + size - cy) % size) * size;
+            for x in 0..size {
+                energy[y * size + x] += sign * kernel[row + (x + size - cx) % size];
+            }
+        }
+    };
+    // Densest set pixel and emptiest unset pixel
+    let tightest_cluster = |pattern: &[bool], energy: &[f64]| {
+        (0..n)
+            .filter(|&i| pattern[i])
+            .max_by(|&a, &b| energy[a].total_cmp(&energy[b]))
+            .unwrap()
+    };
+    let largest_void = |pattern: &[bool], energy: &[f64]| {
+        (0..n)
+            .filter(|&i| !pattern[i])
+            .min_by(|&a, &b| energy[a].total_cmp(&energy[b]))
+            .unwrap()
+    };
+
+    // Initial pattern: a tenth of the pixels set at random, then relaxed by moving the
+    // tightest cluster into the largest void until that no longer changes anything
+    let ones = (n / 10).max(1);
+    let mut cells: Vec<usize> = (0..n).collect();
+    Pcg32::new(seed, 0).shuffle(&mut cells);
+    let mut pattern = vec![false; n];
+    let mut energy = vec![0.0; n];
+    for &cell in &cells[..ones] {
+        pattern[cell] = true;
+        splat(&mut energy, cell, 1.0);
+    }
+    for _ in 0..n {
+        let cluster = tightest_cluster(&pattern, &energy);
+        pattern[cluster] = false;
+        splat(&mut energy, cluster, -1.0);
+        let void = largest_void(&pattern, &energy);
+        pattern[void] = true;
+        splat(&mut energy, void, 1.0);
+        if void == cluster {
+            break;
+        }
+    }
+
+    let mut rank = vec![0; n];
+
+    // Rank the initial pixels by removing tightest clusters one at a time
+    let (mut p, mut e) = (pattern.clone(), energy.clone());
+    for r in (0..ones).rev() {
+        let cell = tightest_cluster(&p, &e);
+        p[cell] = false;
+        splat(&mut e, cell, -1.0);
+        rank[cell] = r;
+    }
+
+    // Then rank the rest by filling the largest voids
+    let (mut p, mut e) = (pattern, energy);
+    for r in ones..n {
+        let cell = largest_void(&p, &e);
+        p[cell] = true;
+        splat(&mut e, cell, 1.0);
+        rank[cell] = r;
+    }
+
+    rank.iter().map(|&r| (r as f64 + 0.5) / n as f64).collect()
+}
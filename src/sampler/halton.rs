@@ -0,0 +1,69 @@
+use crate::rng::{Pcg32, mix64};
+use crate::sampler::{Sampler, bits_to_unit, pixel_id, pixel_key};
+
+const PRIMES: [u64; 48] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
+    197, 199, 211, 223,
+];
+
+// Halton sequence over each pixel's samples, one prime base per dimension. Every pixel shifts
+// the sequence by its own random offset (Cranley-Patterson rotation) so neighbouring pixels
+// don't repeat the same pattern. Dimensions past the prime table fall back to uniform values.
+pub struct HaltonSampler {
+    seed: u64,
+    key: u64,
+    index: u32,
+    dimension: usize,
+    rng: Pcg32,
+}
+
+impl HaltonSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            key: 0,
+            index: 0,
+            dimension: 0,
+            rng: Pcg32::new(seed, 0),
+        }
+    }
+}
+
+impl Sampler for HaltonSampler {
+    fn start_sample(&mut self, x: u32, y: u32, index: u32) {
+        self.key = pixel_key(self.seed, x, y);
+        self.index = index;
+        self.dimension = 0;
+        self.rng = Pcg32::for_sample(self.seed, pixel_id(x, y), index as u64);
+    }
+
+    fn next_1d(&mut self) -> f64 {
+        let dimension = self.dimension;
+        self.dimension += 1;
+        if dimension >= PRIMES.len() {
+            return self.rng.next_f64();
+        }
+
+        let offset = bits_to_unit(mix64(self.key ^ dimension as u64) as u32);
+        (radical_inverse(PRIMES[dimension], self.index as u64) + offset).fract()
+    }
+
+    fn next_2d(&mut self) -> (f64, f64) {
+        (self.next_1d(), self.next_1d())
+    }
+}
+
+// Mirrors the base-`base` digits of `a` about the radix point
+fn radical_inverse(base: u64, mut a: u64) -> f64 {
+    let inv_base = 1.0 / base as f64;
+    let mut reversed = 0;
+    let mut inv_base_n = 1.0;
+    while a > 0 {
+        let next = a / base;
+        reversed = reversed * base + (a - next * base);
+        inv_base_n *= inv_base;
+        a = next;
+    }
+    reversed as f64 * inv_base_n
+}
@@ -0,0 +1,31 @@
+use crate::rng::Pcg32;
+use crate::sampler::{Sampler, pixel_id};
+
+// Every dimension is an independent uniform value
+pub struct IndependentSampler {
+    seed: u64,
+    rng: Pcg32,
+}
+
+impl IndependentSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: Pcg32::new(seed, 0),
+        }
+    }
+}
+
+impl Sampler for IndependentSampler {
+    fn start_sample(&mut self, x: u32, y: u32, index: u32) {
+        self.rng = Pcg32::for_sample(self.seed, pixel_id(x, y), index as u64);
+    }
+
+    fn next_1d(&mut self) -> f64 {
+        self.rng.next_f64()
+    }
+
+    fn next_2d(&mut self) -> (f64, f64) {
+        (self.rng.next_f64(), self.rng.next_f64())
+    }
+}
@@ -0,0 +1,62 @@
+use crate::rng::mix64;
+
+pub mod blue_noise;
+pub mod halton;
+pub mod independent;
+pub mod sobol;
+pub mod stratified;
+
+use blue_noise::BlueNoiseSampler;
+use halton::HaltonSampler;
+use independent::IndependentSampler;
+use sobol::SobolSampler;
+use stratified::StratifiedSampler;
+
+// Supplies the values a camera sample consumes. Dimensions are drawn in a fixed order (pixel
+// jitter, lens, time, then the scattering decisions at each bounce), so a sampler can spread
+// each dimension evenly over the samples of a pixel.
+pub trait Sampler {
+    // Begin sample `index` of pixel (x, y); dimensions restart from the first
+    fn start_sample(&mut self, x: u32, y: u32, index: u32);
+
+    // Next dimension, in [0, 1)
+    fn next_1d(&mut self) -> f64;
+
+    // Next two dimensions, stratified jointly where the sampler supports it
+    fn next_2d(&mut self) -> (f64, f64);
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SamplerKind {
+    #[default]
+    Independent, // Uniform random values
+    Stratified, // Jittered strata, shuffled per dimension
+    Halton,     // Halton sequence with a per-pixel rotation
+    Sobol,      // Owen-scrambled Sobol (0,2)-sequence
+    BlueNoise,  // Sobol points offset by a blue-noise tile, so error is spread as blue noise
+}
+
+impl SamplerKind {
+    pub fn create(&self, seed: u64, samples_per_pixel: u32) -> Box<dyn Sampler> {
+        match self {
+            SamplerKind::Independent => Box::new(IndependentSampler::new(seed)),
+            SamplerKind::Stratified => Box::new(StratifiedSampler::new(seed, samples_per_pixel)),
+            SamplerKind::Halton => Box::new(HaltonSampler::new(seed)),
+            SamplerKind::Sobol => Box::new(SobolSampler::new(seed)),
+            SamplerKind::BlueNoise => Box::new(BlueNoiseSampler::new(seed)),
+        }
+    }
+}
+
+pub(crate) fn pixel_id(x: u32, y: u32) -> u64 {
+    ((y as u64) << 32) | x as u64
+}
+
+pub(crate) fn pixel_key(seed: u64, x: u32, y: u32) -> u64 {
+    mix64(seed ^ mix64(pixel_id(x, y)))
+}
+
+// Maps 32 random bits to [0, 1)
+pub(crate) fn bits_to_unit(bits: u32) -> f64 {
+    bits as f64 * (1.0 / (1u64 << 32) as f64)
+}
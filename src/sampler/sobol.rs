@@ -0,0 +1,102 @@
+use crate::rng::mix64;
+use crate::sampler::{Sampler, bits_to_unit, pixel_key};
+
+// Direction numbers for the first two Sobol dimensions, which together form a (0,2)-sequence
+const DIRECTIONS: [[u32; 32]; 2] = sobol_directions();
+
+const fn sobol_directions() -> [[u32; 32]; 2] {
+    let mut v = [[0; 32]; 2];
+    let mut bit = 0;
+    while bit < 32 {
+        v[0][bit] = 1 << (31 - bit);
+        v[1][bit] = if bit == 0 {
+            1 << 31
+        } else {
+            v[1][bit - 1] ^ (v[1][bit - 1] >> 1)
+        };
+        bit += 1;
+    }
+    v
+}
+
+// Owen-scrambled Sobol points (Burley, "Practical Hash-based Owen Scrambling"). Each pair of
+// dimensions is a separately scrambled and shuffled copy of the 2D Sobol sequence, which keeps
+// every power-of-two prefix of a pixel's samples well stratified in each pair.
+pub struct SobolSampler {
+    seed: u64,
+    key: u64,
+    index: u32,
+    dimension: u64,
+}
+
+impl SobolSampler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            key: 0,
+            index: 0,
+            dimension: 0,
+        }
+    }
+
+    fn next_dimension_seed(&mut self) -> u64 {
+        self.dimension += 1;
+        mix64(self.key ^ mix64(self.dimension))
+    }
+}
+
+impl Sampler for SobolSampler {
+    fn start_sample(&mut self, x: u32, y: u32, index: u32) {
+        self.key = pixel_key(self.seed, x, y);
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    fn next_1d(&mut self) -> f64 {
+        let seed = self.next_dimension_seed();
+        bits_to_unit(scrambled_sobol(self.index, seed)[0])
+    }
+
+    fn next_2d(&mut self) -> (f64, f64) {
+        let seed = self.next_dimension_seed();
+        let [x, y] = scrambled_sobol(self.index, seed);
+        (bits_to_unit(x), bits_to_unit(y))
+    }
+}
+
+pub(crate) fn sobol(index: u32, dimension: usize) -> u32 {
+    let mut x = 0;
+    let mut i = index;
+    let mut bit = 0;
+    while i != 0 {
+        if i & 1 != 0 {
+            x ^= DIRECTIONS[dimension][bit];
+        }
+        i >>= 1;
+        bit += 1;
+    }
+    x
+}
+
+// Shuffled, Owen-scrambled 2D Sobol point `index` for the given seed
+pub(crate) fn scrambled_sobol(index: u32, seed: u64) -> [u32; 2] {
+    let index = nested_uniform_scramble(index, seed as u32);
+    [
+        nested_uniform_scramble(sobol(index, 0), (seed >> 32) as u32),
+        nested_uniform_scramble(sobol(index, 1), mix64(seed) as u32),
+    ]
+}
+
+// Owen scrambling: every bit is flipped by a hash of the bits above it
+fn nested_uniform_scramble(x: u32, seed: u32) -> u32 {
+    laine_karras_permutation(x.reverse_bits(), seed).reverse_bits()
+}
+
+fn laine_karras_permutation(mut x: u32, seed: u32) -> u32 {
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50b47c);
+    x ^= x.wrapping_mul(0xb82f1e52);
+    x ^= x.wrapping_mul(0xc7afe638);
+    x ^= x.wrapping_mul(0x8d22f6e6);
+    x
+}
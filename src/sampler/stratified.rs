@@ -0,0 +1,108 @@
+use crate::rng::{Pcg32, mix64};
+use crate::sampler::{Sampler, pixel_id, pixel_key};
+
+// Jittered stratification: each 1D dimension is split into `samples_per_pixel` strata and each
+// 2D dimension into a grid of about as many cells. Sample `i` takes a different stratum in each
+// dimension through a hashed permutation, so dimensions don't line up with one another.
+pub struct StratifiedSampler {
+    seed: u64,
+    samples: u32,
+    x_strata: u32,
+    y_strata: u32,
+    key: u64,
+    index: u32,
+    dimension: u64,
+    rng: Pcg32,
+}
+
+impl StratifiedSampler {
+    pub fn new(seed: u64, samples_per_pixel: u32) -> Self {
+        let samples = samples_per_pixel.max(1);
+        let x_strata = ((samples as f64).sqrt() as u32).max(1);
+        Self {
+            seed,
+            samples,
+            x_strata,
+            y_strata: samples.div_ceil(x_strata),
+            key: 0,
+            index: 0,
+            dimension: 0,
+            rng: Pcg32::new(seed, 0),
+        }
+    }
+
+    fn next_permutation_key(&mut self) -> u32 {
+        self.dimension += 1;
+        mix64(self.key ^ self.dimension) as u32
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn start_sample(&mut self, x: u32, y: u32, index: u32) {
+        self.key = pixel_key(self.seed, x, y);
+        self.index = index;
+        self.dimension = 0;
+        self.rng = Pcg32::for_sample(self.seed, pixel_id(x, y), index as u64);
+    }
+
+    fn next_1d(&mut self) -> f64 {
+        let p = self.next_permutation_key();
+        // Samples past the planned count have no stratum left
+        if self.index >= self.samples {
+            return self.rng.next_f64();
+        }
+
+        let stratum = permutation_element(self.index, self.samples, p);
+        (stratum as f64 + self.rng.next_f64()) / self.samples as f64
+    }
+
+    fn next_2d(&mut self) -> (f64, f64) {
+        let p = self.next_permutation_key();
+        let cells = self.x_strata * self.y_strata;
+        if self.index >= cells {
+            return (self.rng.next_f64(), self.rng.next_f64());
+        }
+
+        let cell = permutation_element(self.index, cells, p);
+        let (sx, sy) = (cell % self.x_strata, cell / self.x_strata);
+        (
+            (sx as f64 + self.rng.next_f64()) / self.x_strata as f64,
+            (sy as f64 + self.rng.next_f64()) / self.y_strata as f64,
+        )
+    }
+}
+
+// Element `i` of a pseudo-random permutation of [0, l) selected by `p`, without storing the
+// permutation (Kensler, "Correlated Multi-Jittered Sampling")
+pub(crate) fn permutation_element(mut i: u32, l: u32, p: u32) -> u32 {
+    let mut w = l - 1;
+    w |= w >> 1;
+    w |= w >> 2;
+    w |= w >> 4;
+    w |= w >> 8;
+    w |= w >> 16;
+    loop {
+        i ^= p;
+        i = i.wrapping_mul(0xe170893d);
+        i ^= p >> 16;
+        i ^= (i & w) >> 4;
+        i ^= p >> 8;
+        i = i.wrapping_mul(0x0929eb3f);
+        i ^= p >> 23;
+        i ^= (i & w) >> 1;
+        i = i.wrapping_mul(1 | p >> 27);
+        i = i.wrapping_mul(0x6935fa69);
+        i ^= (i & w) >> 11;
+        i = i.wrapping_mul(0x74dcb303);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0x9e501cc3);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0xc860a3df);
+        i &= w;
+        i ^= i >> 5;
+        if i < l {
+            break;
+        }
+    }
+    (i.wrapping_add(p)) % l
+}
@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use toml::Spanned;
+
+pub(crate) type Triple = [f64; 3];
+
+// Serde model of a scene file. Field names follow the Rust types they build, so `lookfrom`
+// is `Camera::lookfrom`, `refraction_index` is `Dielectric::new`'s argument and so on.
+// Textures and materials are declared in named tables and referenced by name.
+//
+// Only top-level entries carry spans: serde buffers internally tagged enums, which loses the
+// position information `Spanned` relies on.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct SceneFile {
+    pub camera: Option<Spanned<CameraDesc>>,
+    #[serde(default)]
+    pub textures: BTreeMap<String, Spanned<TextureDesc>>,
+    #[serde(default)]
+    pub materials: BTreeMap<String, Spanned<MaterialDesc>>,
+    #[serde(default)]
+    pub objects: Vec<Spanned<ObjectDesc>>,
+}
+
+// Anything left out keeps its `Camera::new` value
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct CameraDesc {
+    pub aspect_ratio: Option<f64>,
+    pub image_width: Option<i32>,
+    pub samples_per_pixel: Option<i32>,
+    pub max_depth: Option<i32>,
+    pub background: Option<BackgroundDesc>,
+    pub light_sampling: Option<bool>,
+    pub vfov: Option<f64>,
+    pub lookfrom: Option<Triple>,
+    pub lookat: Option<Triple>,
+    pub vup: Option<Triple>,
+    pub defocus_angle: Option<f64>,
+    pub focus_dist: Option<f64>,
+    pub shutter_open: Option<f64>,
+    pub shutter_close: Option<f64>,
+    pub seed: Option<u64>,
+    pub sampler: Option<SamplerDesc>,
+    pub adaptive: Option<AdaptiveDesc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub(crate) enum BackgroundDesc {
+    Sky,
+    Solid { colour: Triple },
+    Gradient { bottom: Triple, top: Triple },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SamplerDesc {
+    Independent,
+    Stratified,
+    Halton,
+    Sobol,
+    BlueNoise,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct AdaptiveDesc {
+    pub max_error: f64,
+    pub max_samples: i32,
+    pub min_samples: Option<i32>,
+    pub samples_per_round: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub(crate) enum TextureDesc {
+    Solid {
+        colour: Triple,
+    },
+    Checker {
+        scale: f64,
+        even: Triple,
+        odd: Triple,
+    },
+    // Path is relative to the scene file
+    Image {
+        path: String,
+    },
+    Noise {
+        #[serde(default)]
+        seed: u64,
+        scale: f64,
+        colour: Option<Triple>,
+    },
+    Marble {
+        #[serde(default)]
+        seed: u64,
+        scale: f64,
+        colour: Triple,
+    },
+    Wood {
+        #[serde(default)]
+        seed: u64,
+        scale: f64,
+        light: Triple,
+        dark: Triple,
+    },
+}
+
+// Materials with a colour take either a constant (`albedo`, or `emit` for lights) or the name
+// of a texture
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub(crate) enum MaterialDesc {
+    Lambertian {
+        albedo: Option<Triple>,
+        texture: Option<String>,
+    },
+    Metal {
+        albedo: Option<Triple>,
+        texture: Option<String>,
+        #[serde(default)]
+        fuzz: f64,
+    },
+    Dielectric {
+        refraction_index: f64,
+    },
+    DiffuseLight {
+        emit: Option<Triple>,
+        texture: Option<String>,
+    },
+    Isotropic {
+        albedo: Option<Triple>,
+        texture: Option<String>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub(crate) enum ObjectDesc {
+    // `centre_end` makes the sphere move from `centre` at time 0 to `centre_end` at time 1,
+    // whatever the camera shutter is set to
+    Sphere {
+        centre: Triple,
+        radius: f64,
+        centre_end: Option<Triple>,
+        material: String,
+    },
+    Quad {
+        q: Triple,
+        u: Triple,
+        v: Triple,
+        material: String,
+    },
+    Disk {
+        centre: Triple,
+        normal: Triple,
+        radius: f64,
+        material: String,
+    },
+    Triangle {
+        p0: Triple,
+        p1: Triple,
+        p2: Triple,
+        material: String,
+    },
+    Cuboid {
+        min: Triple,
+        max: Triple,
+        material: String,
+    },
+    Plane {
+        point: Triple,
+        normal: Triple,
+        material: String,
+    },
+    // OBJ model; `material` is used for faces without an MTL material
+    Mesh {
+        path: String,
+        material: String,
+    },
+    ConstantMedium {
+        boundary: Box<ObjectDesc>,
+        density: f64,
+        albedo: Option<Triple>,
+        texture: Option<String>,
+    },
+    // Scales, then rotates (about x, then y, then z, in degrees), then translates `object`
+    Instance {
+        object: Box<ObjectDesc>,
+        scale: Option<Triple>,
+        rotate: Option<Triple>,
+        translate: Option<Triple>,
+    },
+}
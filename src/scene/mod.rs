@@ -0,0 +1,695 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::hash::Hasher;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use toml::Spanned;
+
+use crate::camera::{AdaptiveSampling, Background, Camera};
+use crate::checkpoint::Fingerprint;
+use crate::hittable::constant_medium::ConstantMedium;
+use crate::hittable::cuboid::Cuboid;
+use crate::hittable::disk::Disk;
+use crate::hittable::plane::Plane;
+use crate::hittable::quad::Quad;
+use crate::hittable::sphere::Sphere;
+use crate::hittable::transformed::Transformed;
+use crate::hittable::triangle::Triangle;
+use crate::hittable::{Hittable, HittableList};
+use crate::material::Material;
+use crate::material::dielectric::Dielectric;
+use crate::material::diffuse_light::DiffuseLight;
+use crate::material::isotropic::Isotropic;
+use crate::material::lambertian::Lambertian;
+use crate::material::metal::Metal;
+use crate::obj::ObjModel;
+use crate::sampler::SamplerKind;
+use crate::texture::Texture;
+use crate::texture::checker::CheckerTexture;
+use crate::texture::image::ImageTexture;
+use crate::texture::noise::{MarbleTexture, NoiseTexture, WoodTexture};
+use crate::texture::solid::SolidColour;
+use crate::transform::Transform;
+use crate::vec3::Vec3;
+
+mod format;
+
+use format::{
+    BackgroundDesc, CameraDesc, MaterialDesc, ObjectDesc, SamplerDesc, SceneFile, TextureDesc,
+    Triple,
+};
+
+// A scene read from a TOML file or built from a preset: the camera, and the objects to render
+// (not yet in a BVH)
+pub struct Scene {
+    pub camera: Camera,
+    pub world: HittableList,
+    pub fingerprint: u64, // Hash of the scene file and the files it uses, or of the preset name
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    // Syntax errors, bad or missing fields, unknown names and files that fail to load
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io { path, source } => {
+                write!(f, "could not read {}: {}", path.display(), source)
+            }
+            SceneError::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+        }
+    }
+}
+
+impl Error for SceneError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SceneError::Io { source, .. } => Some(source),
+            SceneError::Parse { .. } => None,
+        }
+    }
+}
+
+impl Scene {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Scene, SceneError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|source| SceneError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Scene::parse(&source, path)
+    }
+
+    // Builds a scene from TOML text. `path` names the file in errors, and relative mesh and
+    // image paths are resolved against its directory.
+    pub fn parse(source: &str, path: &Path) -> Result<Scene, SceneError> {
+        let loader = Loader {
+            source,
+            path,
+            base_dir: path.parent().unwrap_or(Path::new("")),
+            files: RefCell::new(vec![]),
+        };
+
+        let file: SceneFile = toml::from_str(source)
+            .map_err(|e| loader.error(e.span().unwrap_or(0..0), e.message()))?;
+
+        let mut textures = HashMap::new();
+        for (name, desc) in &file.textures {
+            let texture = loader
+                .texture(desc.get_ref())
+                .map_err(|e| loader.entry_error(desc.span(), &format!("textures.{}", name), e))?;
+            textures.insert(name.clone(), texture);
+        }
+
+        let mut materials = HashMap::new();
+        for (name, desc) in &file.materials {
+            let material = loader
+                .material(desc.get_ref(), &textures)
+                .map_err(|e| loader.entry_error(desc.span(), &format!("materials.{}", name), e))?;
+            materials.insert(name.clone(), material);
+        }
+
+        let mut world = HittableList::new();
+        for (i, desc) in file.objects.iter().enumerate() {
+            let object = loader
+                .object(desc.get_ref(), &textures, &materials)
+                .map_err(|e| loader.entry_error(desc.span(), &format!("objects[{}]", i), e))?;
+            world.add_shared(object);
+        }
+
+        let camera = match file.camera {
+            Some(desc) => loader.camera(desc)?,
+            None => Camera::new(),
+        };
+
+        // Referenced files are hashed as well, so editing a mesh or texture invalidates checkpoints
+        let mut fingerprint = Fingerprint::new();
+        fingerprint.write(source.as_bytes());
+        let mut files = loader.files.into_inner();
+        files.sort();
+        files.dedup();
+        for file in files {
+            let contents =
+                fs::read(&file).map_err(|source| SceneError::Io { path: file, source })?;
+            fingerprint.write_u64(contents.len() as u64);
+            fingerprint.write(&contents);
+        }
+        Ok(Scene {
+            camera,
+            world,
+            fingerprint: fingerprint.finish(),
+        })
+    }
+}
+
+// Applies a `name=value` camera setting, such as `vfov=40`, `lookfrom=13,2,3` or
+// `sampler=sobol`, written as the camera table of a scene file would write it. Commas make a
+// bare value an array and anything else that isn't valid TOML is taken as a string.
+pub fn override_camera(camera: &mut Camera, setting: &str) -> Result<(), String> {
+    let (name, value) = setting
+        .split_once('=')
+        .ok_or_else(|| String::from("expected NAME=VALUE"))?;
+    let (name, value) = (name.trim(), value.trim());
+
+    let mut desc = toml::from_str::<CameraDesc>(&format!("{} = {}", name, value));
+    if desc.is_err() && !value.starts_with(['"', '[', '{']) {
+        let value = if value.contains(',') {
+            format!("[{}]", value)
+        } else {
+            format!("{:?}", value)
+        };
+        desc = toml::from_str(&format!("{} = {}", name, value));
+    }
+    let desc = desc.map_err(|e| e.message().to_string())?;
+    apply_camera(camera, desc)
+}
+
+// Problem with one entry. `entry` locates it within the entry being loaded, such as
+// ".object" for an instance's object; the top-level entry name and line are added by the caller.
+struct EntryError {
+    entry: String,
+    message: String,
+}
+
+impl EntryError {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            entry: String::new(),
+            message: message.into(),
+        }
+    }
+
+    fn in_entry(self, entry: &str) -> Self {
+        Self {
+            entry: format!("{}{}", entry, self.entry),
+            ..self
+        }
+    }
+}
+
+type EntryResult<T> = Result<T, EntryError>;
+
+struct Loader<'a> {
+    source: &'a str,
+    path: &'a Path,
+    base_dir: &'a Path,
+    files: RefCell<Vec<PathBuf>>, // Meshes, materials and images read while loading
+}
+
+impl Loader<'_> {
+    fn error(&self, span: Range<usize>, message: impl Into<String>) -> SceneError {
+        let offset = span.start.min(self.source.len());
+        SceneError::Parse {
+            path: self.path.to_path_buf(),
+            line: self.source[..offset].matches('\n').count() + 1,
+            message: message.into(),
+        }
+    }
+
+    fn entry_error(&self, span: Range<usize>, entry: &str, e: EntryError) -> SceneError {
+        let e = e.in_entry(entry);
+        self.error(span, format!("{}: {}", e.entry, e.message))
+    }
+
+    fn camera(&self, desc: Spanned<CameraDesc>) -> Result<Camera, SceneError> {
+        let span = desc.span();
+        let mut cam = Camera::new();
+        apply_camera(&mut cam, desc.into_inner())
+            .map_err(|message| self.error(span, format!("camera: {}", message)))?;
+        Ok(cam)
+    }
+
+    fn texture(&self, desc: &TextureDesc) -> EntryResult<Arc<dyn Texture>> {
+        Ok(match desc {
+            TextureDesc::Solid { colour } => Arc::new(SolidColour::new(vec3(*colour))),
+            TextureDesc::Checker { scale, even, odd } => {
+                if *scale == 0.0 {
+                    return Err(EntryError::new("scale must not be zero"));
+                }
+                Arc::new(CheckerTexture::from_colours(
+                    *scale,
+                    vec3(*even),
+                    vec3(*odd),
+                ))
+            }
+            TextureDesc::Image { path } => {
+                let file = self.base_dir.join(path);
+                let image = ImageTexture::load(&file)
+                    .map_err(|e| EntryError::new(format!("could not load {}: {}", path, e)))?;
+                self.files.borrow_mut().push(file);
+                Arc::new(image)
+            }
+            TextureDesc::Noise {
+                seed,
+                scale,
+                colour,
+            } => {
+                let noise = NoiseTexture::new(*seed, *scale);
+                match colour {
+                    Some(colour) => Arc::new(noise.with_colour(vec3(*colour))),
+                    None => Arc::new(noise),
+                }
+            }
+            TextureDesc::Marble {
+                seed,
+                scale,
+                colour,
+            } => Arc::new(MarbleTexture::new(*seed, *scale, vec3(*colour))),
+            TextureDesc::Wood {
+                seed,
+                scale,
+                light,
+                dark,
+            } => Arc::new(WoodTexture::new(*seed, *scale, vec3(*light), vec3(*dark))),
+        })
+    }
+
+    fn material(
+        &self,
+        desc: &MaterialDesc,
+        textures: &HashMap<String, Arc<dyn Texture>>,
+    ) -> EntryResult<Arc<dyn Material>> {
+        let colour = |field: &str, colour: &Option<Triple>, texture: &Option<String>| {
+            colour_or_texture(field, colour, texture, textures)
+        };
+
+        Ok(match desc {
+            MaterialDesc::Lambertian { albedo, texture } => {
+                Arc::new(Lambertian::from_texture(colour("albedo", albedo, texture)?))
+            }
+            MaterialDesc::Metal {
+                albedo,
+                texture,
+                fuzz,
+            } => {
+                if *fuzz < 0.0 {
+                    return Err(EntryError::new("fuzz must not be negative"));
+                }
+                Arc::new(Metal::from_texture(
+                    colour("albedo", albedo, texture)?,
+                    *fuzz,
+                ))
+            }
+            MaterialDesc::Dielectric { refraction_index } => {
+                if *refraction_index <= 0.0 {
+                    return Err(EntryError::new("refraction_index must be positive"));
+                }
+                Arc::new(Dielectric::new(*refraction_index))
+            }
+            MaterialDesc::DiffuseLight { emit, texture } => {
+                Arc::new(DiffuseLight::from_texture(colour("emit", emit, texture)?))
+            }
+            MaterialDesc::Isotropic { albedo, texture } => {
+                Arc::new(Isotropic::from_texture(colour("albedo", albedo, texture)?))
+            }
+        })
+    }
+
+    fn object(
+        &self,
+        desc: &ObjectDesc,
+        textures: &HashMap<String, Arc<dyn Texture>>,
+        materials: &HashMap<String, Arc<dyn Material>>,
+    ) -> EntryResult<Arc<dyn Hittable>> {
+        let material = |name: &String| {
+            materials
+                .get(name)
+                .cloned()
+                .ok_or_else(|| EntryError::new(format!("unknown material \"{}\"", name)))
+        };
+        let positive = |value: f64, field: &str| {
+            if value > 0.0 {
+                Ok(value)
+            } else {
+                Err(EntryError::new(format!("{} must be positive", field)))
+            }
+        };
+        let non_zero = |value: Vec3, field: &str| {
+            if value.near_zero() {
+                Err(EntryError::new(format!("{} must not be zero", field)))
+            } else {
+                Ok(value)
+            }
+        };
+
+        Ok(match desc {
+            ObjectDesc::Sphere {
+                centre,
+                radius,
+                centre_end,
+                material: name,
+            } => {
+                let radius = positive(*radius, "radius")?;
+                match centre_end {
+                    Some(end) => Arc::new(Sphere::new_moving(
+                        vec3(*centre),
+                        vec3(*end),
+                        radius,
+                        material(name)?,
+                    )),
+                    None => Arc::new(Sphere::new(vec3(*centre), radius, material(name)?)),
+                }
+            }
+            ObjectDesc::Quad {
+                q,
+                u,
+                v,
+                material: name,
+            } => {
+                let (u, v) = (vec3(*u), vec3(*v));
+                if u.cross(v).near_zero() {
+                    return Err(EntryError::new("u and v must be non-zero and not parallel"));
+                }
+                Arc::new(Quad::new(vec3(*q), u, v, material(name)?))
+            }
+            ObjectDesc::Disk {
+                centre,
+                normal,
+                radius,
+                material: name,
+            } => Arc::new(Disk::new(
+                vec3(*centre),
+                non_zero(vec3(*normal), "normal")?,
+                positive(*radius, "radius")?,
+                material(name)?,
+            )),
+            ObjectDesc::Triangle {
+                p0,
+                p1,
+                p2,
+                material: name,
+            } => {
+                let (p0, p1, p2) = (vec3(*p0), vec3(*p1), vec3(*p2));
+                if (p1 - p0).cross(p2 - p0).near_zero() {
+                    return Err(EntryError::new("p0, p1 and p2 must not lie on a line"));
+                }
+                Arc::new(Triangle::new(p0, p1, p2, material(name)?))
+            }
+            ObjectDesc::Cuboid {
+                min,
+                max,
+                material: name,
+            } => Arc::new(Cuboid::new(vec3(*min), vec3(*max), material(name)?)),
+            ObjectDesc::Plane {
+                point,
+                normal,
+                material: name,
+            } => Arc::new(Plane::new(
+                vec3(*point),
+                non_zero(vec3(*normal), "normal")?,
+                material(name)?,
+            )),
+            ObjectDesc::Mesh {
+                path,
+                material: name,
+            } => {
+                let model = ObjModel::load(self.base_dir.join(path), material(name)?)
+                    .map_err(|e| EntryError::new(e.to_string()))?;
+                self.files.borrow_mut().extend_from_slice(model.sources());
+                Arc::new(model)
+            }
+            ObjectDesc::ConstantMedium {
+                boundary,
+                density,
+                albedo,
+                texture,
+            } => {
+                let density = positive(*density, "density")?;
+                let tex = colour_or_texture("albedo", albedo, texture, textures)?;
+                let boundary = self
+                    .object(boundary, textures, materials)
+                    .map_err(|e| e.in_entry(".boundary"))?;
+                Arc::new(ConstantMedium::from_texture(boundary, density, tex))
+            }
+            ObjectDesc::Instance {
+                object,
+                scale,
+                rotate,
+                translate,
+            } => {
+                let object = self
+                    .object(object, textures, materials)
+                    .map_err(|e| e.in_entry(".object"))?;
+                let mut transform = Transform::identity();
+                if let Some(scale) = scale {
+                    if scale.contains(&0.0) {
+                        return Err(EntryError::new("scale components must not be zero"));
+                    }
+                    transform = transform.scale(vec3(*scale));
+                }
+                if let Some([x, y, z]) = *rotate {
+                    transform = transform.rotate_x(x).rotate_y(y).rotate_z(z);
+                }
+                if let Some(translate) = translate {
+                    transform = transform.translate(vec3(*translate));
+                }
+                Arc::new(Transformed::new(object, transform))
+            }
+        })
+    }
+}
+
+// Applies the fields set in `desc` onto `cam`
+fn apply_camera(cam: &mut Camera, desc: CameraDesc) -> Result<(), String> {
+    if let Some(aspect_ratio) = desc.aspect_ratio {
+        if aspect_ratio <= 0.0 {
+            return Err("aspect_ratio must be positive".into());
+        }
+        cam.aspect_ratio = aspect_ratio;
+    }
+    if let Some(image_width) = desc.image_width {
+        if image_width < 1 {
+            return Err("image_width must be at least 1".into());
+        }
+        cam.image_width = image_width;
+    }
+    if let Some(samples_per_pixel) = desc.samples_per_pixel {
+        if samples_per_pixel < 1 {
+            return Err("samples_per_pixel must be at least 1".into());
+        }
+        cam.samples_per_pixel = samples_per_pixel;
+    }
+    if let Some(max_depth) = desc.max_depth {
+        if max_depth < 1 {
+            return Err("max_depth must be at least 1".into());
+        }
+        cam.max_depth = max_depth;
+    }
+    if let Some(background) = desc.background {
+        cam.background = match background {
+            BackgroundDesc::Sky => Background::Sky,
+            BackgroundDesc::Solid { colour } => Background::Solid(vec3(colour)),
+            BackgroundDesc::Gradient { bottom, top } => Background::Gradient {
+                bottom: vec3(bottom),
+                top: vec3(top),
+            },
+        };
+    }
+    if let Some(light_sampling) = desc.light_sampling {
+        cam.light_sampling = light_sampling;
+    }
+    if let Some(vfov) = desc.vfov {
+        cam.vfov = vfov;
+    }
+    if let Some(lookfrom) = desc.lookfrom {
+        cam.lookfrom = vec3(lookfrom);
+    }
+    if let Some(lookat) = desc.lookat {
+        cam.lookat = vec3(lookat);
+    }
+    if let Some(vup) = desc.vup {
+        cam.vup = vec3(vup);
+    }
+    if let Some(defocus_angle) = desc.defocus_angle {
+        cam.defocus_angle = defocus_angle;
+    }
+    if let Some(focus_dist) = desc.focus_dist {
+        cam.focus_dist = focus_dist;
+    }
+    if let Some(shutter_open) = desc.shutter_open {
+        cam.shutter_open = shutter_open;
+    }
+    if let Some(shutter_close) = desc.shutter_close {
+        cam.shutter_close = shutter_close;
+    }
+    if let Some(seed) = desc.seed {
+        cam.seed = seed;
+    }
+    if let Some(sampler) = desc.sampler {
+        cam.sampler = match sampler {
+            SamplerDesc::Independent => SamplerKind::Independent,
+            SamplerDesc::Stratified => SamplerKind::Stratified,
+            SamplerDesc::Halton => SamplerKind::Halton,
+            SamplerDesc::Sobol => SamplerKind::Sobol,
+            SamplerDesc::BlueNoise => SamplerKind::BlueNoise,
+        };
+    }
+    if let Some(adaptive) = desc.adaptive {
+        if adaptive.max_samples < 1 {
+            return Err("adaptive.max_samples must be at least 1".into());
+        }
+        let mut settings = AdaptiveSampling::new(adaptive.max_error, adaptive.max_samples);
+        if let Some(min_samples) = adaptive.min_samples {
+            settings.min_samples = min_samples.clamp(1, adaptive.max_samples);
+        }
+        if let Some(samples_per_round) = adaptive.samples_per_round {
+            settings.samples_per_round = samples_per_round.max(1);
+        }
+        cam.adaptive = Some(settings);
+    }
+    Ok(())
+}
+
+// Texture for a material or medium colour given either as a constant or a texture name
+fn colour_or_texture(
+    field: &str,
+    colour: &Option<Triple>,
+    texture: &Option<String>,
+    textures: &HashMap<String, Arc<dyn Texture>>,
+) -> EntryResult<Arc<dyn Texture>> {
+    match (colour, texture) {
+        (Some(colour), None) => Ok(Arc::new(SolidColour::new(vec3(*colour)))),
+        (None, Some(name)) => textures
+            .get(name)
+            .cloned()
+            .ok_or_else(|| EntryError::new(format!("unknown texture \"{}\"", name))),
+        (Some(_), Some(_)) => Err(EntryError::new(format!(
+            "give either {} or texture, not both",
+            field
+        ))),
+        (None, None) => Err(EntryError::new(format!("missing {} (or texture)", field))),
+    }
+}
+
+fn vec3([x, y, z]: Triple) -> Vec3 {
+    Vec3::new(x, y, z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MATERIALS: &str = "\
+[materials.red]
+type = \"lambertian\"
+albedo = [0.7, 0.1, 0.1]
+";
+
+    fn parse(source: &str) -> Result<Scene, SceneError> {
+        Scene::parse(source, Path::new("test.toml"))
+    }
+
+    // Returns the line and message of the parse error `source` must fail with
+    fn parse_error(source: &str) -> (usize, String) {
+        match parse(source) {
+            Err(SceneError::Parse { line, message, .. }) => (line, message),
+            Err(e) => panic!("expected a parse error, got {}", e),
+            Ok(_) => panic!("expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn loads_the_camera_and_objects() {
+        let source = format!(
+            "[camera]\nimage_width = 64\nmax_depth = 5\n\n{}\n\
+             [[objects]]\ntype = \"sphere\"\ncentre = [0, 0, -1]\nradius = 0.5\nmaterial = \"red\"\n\n\
+             [[objects]]\ntype = \"instance\"\nrotate = [0, 45, 0]\n\
+             [objects.object]\ntype = \"cuboid\"\nmin = [0, 0, 0]\nmax = [1, 1, 1]\nmaterial = \"red\"\n",
+            MATERIALS
+        );
+        let scene = parse(&source).unwrap();
+        assert_eq!(scene.camera.image_width, 64);
+        assert_eq!(scene.camera.max_depth, 5);
+        assert_eq!(scene.world.len(), 2);
+        assert_eq!(scene.fingerprint, parse(&source).unwrap().fingerprint);
+        assert_ne!(
+            scene.fingerprint,
+            parse(&source.replace("64", "65")).unwrap().fingerprint
+        );
+    }
+
+    #[test]
+    fn reports_bad_objects_at_their_line() {
+        let sphere = |radius: &str, material: &str| {
+            format!(
+                "{}\n[[objects]]\ntype = \"sphere\"\ncentre = [0, 0, 0]\nradius = {}\nmaterial = \"{}\"\n",
+                MATERIALS, radius, material
+            )
+        };
+        let (line, message) = parse_error(&sphere("1", "blue"));
+        assert_eq!(line, 5);
+        assert_eq!(message, "objects[0]: unknown material \"blue\"");
+
+        let (line, message) = parse_error(&sphere("-1", "red"));
+        assert_eq!(line, 5);
+        assert_eq!(message, "objects[0]: radius must be positive");
+
+        let triangle = format!(
+            "{}\n[[objects]]\ntype = \"triangle\"\np0 = [0, 0, 0]\np1 = [1, 1, 1]\np2 = [2, 2, 2]\nmaterial = \"red\"\n",
+            MATERIALS
+        );
+        let (_, message) = parse_error(&triangle);
+        assert_eq!(message, "objects[0]: p0, p1 and p2 must not lie on a line");
+
+        let quad = format!(
+            "{}\n[[objects]]\ntype = \"quad\"\nq = [0, 0, 0]\nu = [1, 0, 0]\nv = [2, 0, 0]\nmaterial = \"red\"\n",
+            MATERIALS
+        );
+        let (_, message) = parse_error(&quad);
+        assert_eq!(
+            message,
+            "objects[0]: u and v must be non-zero and not parallel"
+        );
+
+        let instance = format!(
+            "{}\n[[objects]]\ntype = \"instance\"\nscale = [1, 0, 1]\n\
+             [objects.object]\ntype = \"sphere\"\ncentre = [0, 0, 0]\nradius = 1\nmaterial = \"red\"\n",
+            MATERIALS
+        );
+        let (_, message) = parse_error(&instance);
+        assert_eq!(message, "objects[0]: scale components must not be zero");
+    }
+
+    #[test]
+    fn reports_syntax_and_field_errors_at_their_line() {
+        let (line, _) = parse_error("[camera]\nimage_width = 64\nvfov = \n");
+        assert_eq!(line, 3);
+
+        let (line, message) = parse_error("[camera]\nimage_width = 64\nfocal_length = 2\n");
+        assert_eq!(line, 3);
+        assert!(message.contains("focal_length"), "{}", message);
+
+        let (line, message) = parse_error("\n[camera]\nmax_depth = 0\n");
+        assert_eq!(line, 2);
+        assert_eq!(message, "camera: max_depth must be at least 1");
+
+        let (line, message) =
+            parse_error("[materials.glass]\ntype = \"dielectric\"\nrefraction_index = -1\n");
+        assert_eq!(line, 1);
+        assert_eq!(
+            message,
+            "materials.glass: refraction_index must be positive"
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use crate::camera::Camera;
+use crate::colour::Colour;
+use crate::hittable::HittableList;
+use crate::hittable::sphere::Sphere;
+use crate::material::lambertian::Lambertian;
+use crate::texture::checker::CheckerTexture;
+use crate::vec3::{Point3, Vec3};
+
+// Two large spheres sharing one solid checker texture
+pub fn checkered_spheres() -> (HittableList, Camera) {
+    let mut world = HittableList::new();
+
+    let checker = Arc::new(Lambertian::from_texture(Arc::new(
+        CheckerTexture::from_colours(0.32, Colour::new(0.2, 0.3, 0.1), Colour::new(0.9, 0.9, 0.9)),
+    )));
+    world.add(Sphere::new(
+        Point3::new(0.0, -10.0, 0.0),
+        10.0,
+        checker.clone(),
+    ));
+    world.add(Sphere::new(Point3::new(0.0, 10.0, 0.0), 10.0, checker));
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 400;
+    cam.samples_per_pixel = 100;
+    cam.max_depth = 50;
+
+    cam.vfov = 20.0;
+    cam.lookfrom = Point3::new(13.0, 2.0, 3.0);
+    cam.lookat = Point3::new(0.0, 0.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+
+    cam.defocus_angle = 0.0;
+
+    (world, cam)
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use crate::camera::{Background, Camera};
+use crate::colour::Colour;
+use crate::hittable::HittableList;
+use crate::hittable::cuboid::Cuboid;
+use crate::hittable::quad::Quad;
+use crate::hittable::transformed::Transformed;
+use crate::material::diffuse_light::DiffuseLight;
+use crate::material::lambertian::Lambertian;
+use crate::transform::Transform;
+use crate::vec3::{Point3, Vec3};
+
+// The standard Cornell box, 555 units on a side and lit only by the ceiling panel
+pub fn cornell_box() -> (HittableList, Camera) {
+    let mut world = HittableList::new();
+
+    let red = Arc::new(Lambertian::new(Colour::new(0.65, 0.05, 0.05)));
+    let white = Arc::new(Lambertian::new(Colour::new(0.73, 0.73, 0.73)));
+    let green = Arc::new(Lambertian::new(Colour::new(0.12, 0.45, 0.15)));
+    let light = Arc::new(DiffuseLight::new(Colour::new(15.0, 15.0, 15.0)));
+
+    world.add(Quad::new(
+        Point3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        green,
+    ));
+    world.add(Quad::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        red,
+    ));
+    world.add(Quad::new(
+        Point3::new(343.0, 554.0, 332.0),
+        Vec3::new(-130.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, -105.0),
+        light,
+    ));
+    world.add(Quad::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        white.clone(),
+    ));
+    world.add(Quad::new(
+        Point3::new(555.0, 555.0, 555.0),
+        Vec3::new(-555.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, -555.0),
+        white.clone(),
+    ));
+    world.add(Quad::new(
+        Point3::new(0.0, 0.0, 555.0),
+        Vec3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        white.clone(),
+    ));
+
+    let tall = Cuboid::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Point3::new(165.0, 330.0, 165.0),
+        white.clone(),
+    );
+    world.add(Transformed::new(
+        tall,
+        Transform::identity()
+            .rotate_y(15.0)
+            .translate(Vec3::new(265.0, 0.0, 295.0)),
+    ));
+
+    let short = Cuboid::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Point3::new(165.0, 165.0, 165.0),
+        white,
+    );
+    world.add(Transformed::new(
+        short,
+        Transform::identity()
+            .rotate_y(-18.0)
+            .translate(Vec3::new(130.0, 0.0, 65.0)),
+    ));
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 1.0;
+    cam.image_width = 600;
+    cam.samples_per_pixel = 200;
+    cam.max_depth = 50;
+    cam.background = Background::Solid(Colour::zero());
+
+    cam.vfov = 40.0;
+    cam.lookfrom = Point3::new(278.0, 278.0, -800.0);
+    cam.lookat = Point3::new(278.0, 278.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+
+    cam.defocus_angle = 0.0;
+
+    (world, cam)
+}
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use crate::camera::Camera;
+use crate::colour::Colour;
+use crate::hittable::HittableList;
+use crate::hittable::sphere::Sphere;
+use crate::material::dielectric::Dielectric;
+use crate::material::lambertian::Lambertian;
+use crate::texture::checker::CheckerTexture;
+use crate::vec3::{Point3, Vec3};
+
+// Nested refractors: a glass ball holding an air bubble with a smaller glass ball inside it.
+// Dielectric doesn't track the medium a ray is in, so the bubble uses the air/glass index
+// ratio to refract correctly against the surrounding glass.
+pub fn glass_in_glass() -> (HittableList, Camera) {
+    let mut world = HittableList::new();
+
+    let ground = Arc::new(Lambertian::from_texture(Arc::new(
+        CheckerTexture::from_colours(0.5, Colour::new(0.2, 0.2, 0.25), Colour::new(0.9, 0.9, 0.9)),
+    )));
+    world.add(Sphere::new(Point3::new(0.0, -1000.0, 0.0), 1000.0, ground));
+
+    let glass = Arc::new(Dielectric::new(1.5));
+    let bubble = Arc::new(Dielectric::new(1.0 / 1.5));
+    world.add(Sphere::new(Point3::new(0.0, 1.2, 0.0), 1.2, glass.clone()));
+    world.add(Sphere::new(Point3::new(0.0, 1.2, 0.0), 1.0, bubble));
+    world.add(Sphere::new(Point3::new(0.0, 1.2, 0.0), 0.5, glass));
+
+    // Coloured balls behind, to show the distortion
+    let colours = [
+        Colour::new(0.8, 0.1, 0.1),
+        Colour::new(0.9, 0.7, 0.1),
+        Colour::new(0.1, 0.6, 0.2),
+        Colour::new(0.1, 0.3, 0.8),
+    ];
+    for (i, &colour) in colours.iter().enumerate() {
+        world.add(Sphere::new(
+            Point3::new(-2.4 + 1.6 * i as f64, 0.5, -3.0),
+            0.5,
+            Arc::new(Lambertian::new(colour)),
+        ));
+    }
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 400;
+    cam.samples_per_pixel = 200;
+    cam.max_depth = 50;
+
+    cam.vfov = 30.0;
+    cam.lookfrom = Point3::new(0.0, 2.0, 7.0);
+    cam.lookat = Point3::new(0.0, 1.0, -0.5);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+
+    cam.defocus_angle = 0.0;
+
+    (world, cam)
+}
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use crate::camera::{Background, Camera};
+use crate::colour::Colour;
+use crate::hittable::HittableList;
+use crate::hittable::quad::Quad;
+use crate::hittable::sphere::Sphere;
+use crate::material::diffuse_light::DiffuseLight;
+use crate::scenes::perlin_spheres::marble_spheres;
+use crate::vec3::{Point3, Vec3};
+
+// The marble spheres in the dark, lit by a rectangular panel and a spherical light overhead
+pub fn lights() -> (HittableList, Camera) {
+    let mut world = marble_spheres();
+
+    let light = Arc::new(DiffuseLight::new(Colour::new(4.0, 4.0, 4.0)));
+    world.add(Quad::new(
+        Point3::new(3.0, 1.0, -2.0),
+        Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(0.0, 2.0, 0.0),
+        light.clone(),
+    ));
+    world.add(Sphere::new(Point3::new(0.0, 7.0, 0.0), 2.0, light));
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 400;
+    cam.samples_per_pixel = 100;
+    cam.max_depth = 50;
+    cam.background = Background::Solid(Colour::zero());
+
+    cam.vfov = 20.0;
+    cam.lookfrom = Point3::new(26.0, 3.0, 6.0);
+    cam.lookat = Point3::new(0.0, 2.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+
+    cam.defocus_angle = 0.0;
+
+    (world, cam)
+}
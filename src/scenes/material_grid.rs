@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use crate::camera::Camera;
+use crate::colour::Colour;
+use crate::hittable::HittableList;
+use crate::hittable::plane::Plane;
+use crate::hittable::sphere::Sphere;
+use crate::material::Material;
+use crate::material::dielectric::Dielectric;
+use crate::material::lambertian::Lambertian;
+use crate::material::metal::Metal;
+use crate::texture::checker::CheckerTexture;
+use crate::vec3::{Point3, Vec3};
+
+const COLUMNS: usize = 6;
+const SPACING: f64 = 1.2;
+
+// Rows of spheres sweeping one material parameter each from left to right. Top to bottom:
+// diffuse albedo from dark to light, metal fuzz from 0 to 1, refraction index from 1.1 to 2.4
+pub fn material_grid() -> (HittableList, Camera) {
+    let mut world = HittableList::new();
+
+    let ground = Arc::new(Lambertian::from_texture(Arc::new(
+        CheckerTexture::from_colours(1.0, Colour::new(0.3, 0.3, 0.3), Colour::new(0.8, 0.8, 0.8)),
+    )));
+    world.add(Plane::new(Point3::zero(), Vec3::new(0.0, 1.0, 0.0), ground));
+
+    for column in 0..COLUMNS {
+        let t = column as f64 / (COLUMNS - 1) as f64;
+        let rows: [Arc<dyn Material>; 3] = [
+            Arc::new(Lambertian::new(
+                (0.1 + 0.8 * t) * Colour::new(0.9, 0.5, 0.3),
+            )),
+            Arc::new(Metal::new(Colour::new(0.8, 0.8, 0.85), t)),
+            Arc::new(Dielectric::new(1.1 + 1.3 * t)),
+        ];
+
+        let x = (column as f64 - 0.5 * (COLUMNS - 1) as f64) * SPACING;
+        for (row, mat) in rows.into_iter().enumerate() {
+            let y = 0.5 + SPACING * (2 - row) as f64;
+            world.add(Sphere::new(Point3::new(x, y, 0.0), 0.5, mat));
+        }
+    }
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 600;
+    cam.samples_per_pixel = 100;
+    cam.max_depth = 50;
+
+    cam.vfov = 25.0;
+    cam.lookfrom = Point3::new(0.0, 3.0, 12.0);
+    cam.lookat = Point3::new(0.0, 1.7, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+
+    cam.defocus_angle = 0.0;
+
+    (world, cam)
+}
@@ -0,0 +1,77 @@
+use std::hash::Hasher;
+
+use crate::checkpoint::Fingerprint;
+use crate::rng::Pcg32;
+use crate::scene::Scene;
+use crate::{DEFAULT_SEED, seed_random};
+
+pub mod checkered_spheres;
+pub mod cornell_box;
+pub mod glass_in_glass;
+pub mod lights;
+pub mod material_grid;
+pub mod perlin_spheres;
+pub mod random_spheres;
+
+// Built-in scenes, each with a camera set up to frame it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    RandomSpheres,
+    CornellBox,
+    CheckeredSpheres,
+    PerlinSpheres,
+    Lights,
+    GlassInGlass,
+    MaterialGrid,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 7] = [
+        Preset::RandomSpheres,
+        Preset::CornellBox,
+        Preset::CheckeredSpheres,
+        Preset::PerlinSpheres,
+        Preset::Lights,
+        Preset::GlassInGlass,
+        Preset::MaterialGrid,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::RandomSpheres => "random-spheres",
+            Preset::CornellBox => "cornell-box",
+            Preset::CheckeredSpheres => "checkered-spheres",
+            Preset::PerlinSpheres => "perlin-spheres",
+            Preset::Lights => "lights",
+            Preset::GlassInGlass => "glass-in-glass",
+            Preset::MaterialGrid => "material-grid",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Preset::ALL.into_iter().find(|preset| preset.name() == name)
+    }
+
+    // The random stream is reset first so a preset comes out the same however much randomness
+    // was used before; that makes the name enough to fingerprint it.
+    pub fn build(&self) -> Scene {
+        seed_random(Pcg32::new(DEFAULT_SEED, 0));
+        let (world, camera) = match self {
+            Preset::RandomSpheres => random_spheres::random_spheres(),
+            Preset::CornellBox => cornell_box::cornell_box(),
+            Preset::CheckeredSpheres => checkered_spheres::checkered_spheres(),
+            Preset::PerlinSpheres => perlin_spheres::perlin_spheres(),
+            Preset::Lights => lights::lights(),
+            Preset::GlassInGlass => glass_in_glass::glass_in_glass(),
+            Preset::MaterialGrid => material_grid::material_grid(),
+        };
+
+        let mut fingerprint = Fingerprint::new();
+        fingerprint.write(self.name().as_bytes());
+        Scene {
+            camera,
+            world,
+            fingerprint: fingerprint.finish(),
+        }
+    }
+}
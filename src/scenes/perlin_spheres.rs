@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use crate::DEFAULT_SEED;
+use crate::camera::Camera;
+use crate::colour::Colour;
+use crate::hittable::HittableList;
+use crate::hittable::sphere::Sphere;
+use crate::material::lambertian::Lambertian;
+use crate::texture::noise::MarbleTexture;
+use crate::vec3::{Point3, Vec3};
+
+// A marbled sphere resting on a marbled ground
+pub fn perlin_spheres() -> (HittableList, Camera) {
+    let world = marble_spheres();
+
+    let mut cam = Camera::new();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 400;
+    cam.samples_per_pixel = 100;
+    cam.max_depth = 50;
+
+    cam.vfov = 20.0;
+    cam.lookfrom = Point3::new(13.0, 2.0, 3.0);
+    cam.lookat = Point3::new(0.0, 0.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+
+    cam.defocus_angle = 0.0;
+
+    (world, cam)
+}
+
+// Shared with the lights demo
+pub(super) fn marble_spheres() -> HittableList {
+    let mut world = HittableList::new();
+
+    let marble = Arc::new(Lambertian::from_texture(Arc::new(MarbleTexture::new(
+        DEFAULT_SEED,
+        4.0,
+        Colour::new(1.0, 1.0, 1.0),
+    ))));
+    world.add(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        marble.clone(),
+    ));
+    world.add(Sphere::new(Point3::new(0.0, 2.0, 0.0), 2.0, marble));
+
+    world
+}
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use crate::colour::Colour;
+use crate::texture::Texture;
+use crate::texture::solid::SolidColour;
+use crate::vec3::Point3;
+
+// Solid 3D checker pattern, alternating between two textures every `scale` world units
+pub struct CheckerTexture {
+    inv_scale: f64,
+    even: Arc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+}
+
+impl CheckerTexture {
+    pub fn new(scale: f64, even: Arc<dyn Texture>, odd: Arc<dyn Texture>) -> Self {
+        Self {
+            inv_scale: 1.0 / scale,
+            even,
+            odd,
+        }
+    }
+
+    pub fn from_colours(scale: f64, c1: Colour, c2: Colour) -> Self {
+        Self::new(
+            scale,
+            Arc::new(SolidColour::new(c1)),
+            Arc::new(SolidColour::new(c2)),
+        )
+    }
+}
+
+impl Texture for CheckerTexture {
+    fn value(&self, u: f64, v: f64, p: Point3) -> Colour {
+        let x = (self.inv_scale * p.x).floor() as i64;
+        let y = (self.inv_scale * p.y).floor() as i64;
+        let z = (self.inv_scale * p.z).floor() as i64;
+
+        if (x + y + z) % 2 == 0 {
+            self.even.value(u, v, p)
+        } else {
+            self.odd.value(u, v, p)
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use image::ImageError;
+
+use crate::colour::{Colour, srgb_to_linear};
+use crate::texture::Texture;
+use crate::vec3::Point3;
+
+// Texture backed by a bitmap, wrapped in both u and v
+pub struct ImageTexture {
+    width: usize,
+    height: usize,
+    pixels: Vec<Colour>, // Linear colours, row by row from the top
+}
+
+impl ImageTexture {
+    pub fn new(width: usize, height: usize, pixels: Vec<Colour>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width * height,
+            "image texture needs width * height pixels"
+        );
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    // Loads an 8-bit sRGB encoded image (PNG or JPEG) and linearizes it
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ImageError> {
+        let img = image::open(path)?.into_rgb8();
+        let (width, height) = (img.width() as usize, img.height() as usize);
+        let pixels = img
+            .pixels()
+            .map(|p| {
+                Colour::new(
+                    srgb_to_linear(p[0] as f64 / 255.0),
+                    srgb_to_linear(p[1] as f64 / 255.0),
+                    srgb_to_linear(p[2] as f64 / 255.0),
+                )
+            })
+            .collect();
+        Ok(Self::new(width, height, pixels))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f64, v: f64, _p: Point3) -> Colour {
+        if self.width == 0 || self.height == 0 {
+            // Debugging aid for empty images
+            return Colour::new(0.0, 1.0, 1.0);
+        }
+
+        let u = u - u.floor();
+        let v = 1.0 - (v - v.floor()); // Flip v to image coordinates
+
+        let i = ((u * self.width as f64) as usize).min(self.width - 1);
+        let j = ((v * self.height as f64) as usize).min(self.height - 1);
+        self.pixels[j * self.width + i]
+    }
+}
@@ -0,0 +1,133 @@
+use crate::colour::Colour;
+use crate::texture::Texture;
+use crate::texture::perlin::Perlin;
+use crate::vec3::Point3;
+
+// Octaves used for the turbulence that distorts marble veins and wood rings
+const TURBULENCE_DEPTH: u32 = 7;
+
+enum NoiseKind {
+    Smooth,
+    Turbulence {
+        depth: u32,
+    },
+    Fbm {
+        octaves: u32,
+        lacunarity: f64,
+        gain: f64,
+    },
+}
+
+// Greyscale noise tinted by a base colour
+pub struct NoiseTexture {
+    noise: Perlin,
+    scale: f64,
+    colour: Colour,
+    kind: NoiseKind,
+}
+
+impl NoiseTexture {
+    pub fn new(seed: u64, scale: f64) -> Self {
+        Self::with_kind(seed, scale, NoiseKind::Smooth)
+    }
+
+    pub fn turbulence(seed: u64, scale: f64, depth: u32) -> Self {
+        Self::with_kind(seed, scale, NoiseKind::Turbulence { depth })
+    }
+
+    pub fn fbm(seed: u64, scale: f64, octaves: u32, lacunarity: f64, gain: f64) -> Self {
+        Self::with_kind(
+            seed,
+            scale,
+            NoiseKind::Fbm {
+                octaves,
+                lacunarity,
+                gain,
+            },
+        )
+    }
+
+    pub fn with_colour(mut self, colour: Colour) -> Self {
+        self.colour = colour;
+        self
+    }
+
+    fn with_kind(seed: u64, scale: f64, kind: NoiseKind) -> Self {
+        Self {
+            noise: Perlin::new(seed),
+            scale,
+            colour: Colour::new(1.0, 1.0, 1.0),
+            kind,
+        }
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: f64, _v: f64, p: Point3) -> Colour {
+        let p = self.scale * p;
+        let intensity = match self.kind {
+            NoiseKind::Smooth => 0.5 * (1.0 + self.noise.noise(p)),
+            NoiseKind::Turbulence { depth } => self.noise.turbulence(p, depth),
+            NoiseKind::Fbm {
+                octaves,
+                lacunarity,
+                gain,
+            } => 0.5 * (1.0 + self.noise.fbm(p, octaves, lacunarity, gain)),
+        };
+        intensity.clamp(0.0, 1.0) * self.colour
+    }
+}
+
+// Sine bands along z distorted by turbulence
+pub struct MarbleTexture {
+    noise: Perlin,
+    scale: f64,
+    colour: Colour,
+}
+
+impl MarbleTexture {
+    pub fn new(seed: u64, scale: f64, colour: Colour) -> Self {
+        Self {
+            noise: Perlin::new(seed),
+            scale,
+            colour,
+        }
+    }
+}
+
+impl Texture for MarbleTexture {
+    fn value(&self, _u: f64, _v: f64, p: Point3) -> Colour {
+        let turbulence = self.noise.turbulence(p, TURBULENCE_DEPTH);
+        0.5 * (1.0 + (self.scale * p.z + 10.0 * turbulence).sin()) * self.colour
+    }
+}
+
+// Concentric rings around the y axis, perturbed by turbulence
+pub struct WoodTexture {
+    noise: Perlin,
+    scale: f64,
+    light: Colour,
+    dark: Colour,
+}
+
+impl WoodTexture {
+    pub fn new(seed: u64, scale: f64, light: Colour, dark: Colour) -> Self {
+        Self {
+            noise: Perlin::new(seed),
+            scale,
+            light,
+            dark,
+        }
+    }
+}
+
+impl Texture for WoodTexture {
+    fn value(&self, _u: f64, _v: f64, p: Point3) -> Colour {
+        let radius = (p.x * p.x + p.z * p.z).sqrt();
+        let rings = self.scale * radius + 2.0 * self.noise.turbulence(p, TURBULENCE_DEPTH);
+        let t = rings - rings.floor();
+        // Sharpen the transition so the dark late wood is a thin band
+        let t = t.powi(3);
+        (1.0 - t) * self.light + t * self.dark
+    }
+}
@@ -0,0 +1,23 @@
+use crate::colour::Colour;
+use crate::texture::Texture;
+use crate::vec3::Point3;
+
+pub struct SolidColour {
+    albedo: Colour,
+}
+
+impl SolidColour {
+    pub fn new(albedo: Colour) -> Self {
+        Self { albedo }
+    }
+
+    pub fn from_rgb(r: f64, g: f64, b: f64) -> Self {
+        Self::new(Colour::new(r, g, b))
+    }
+}
+
+impl Texture for SolidColour {
+    fn value(&self, _u: f64, _v: f64, _p: Point3) -> Colour {
+        self.albedo
+    }
+}
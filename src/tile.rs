@@ -0,0 +1,105 @@
+// Rectangular block of pixels rendered as one unit of work
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    pub x: usize, // Left column
+    pub y: usize, // Top row
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Tile {
+    // Row-major indices of the tile's pixels in an image `image_width` wide
+    pub fn pixel_indices(&self, image_width: usize) -> impl Iterator<Item = usize> + use<> {
+        let tile = *self;
+        (tile.y..tile.y + tile.height)
+            .flat_map(move |y| (tile.x..tile.x + tile.width).map(move |x| y * image_width + x))
+    }
+}
+
+// Order in which tiles are handed to render threads
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TileOrder {
+    Scanline, // Left to right, top to bottom
+    #[default]
+    Spiral, // Outward from the centre, where the subject usually is
+    Hilbert,  // Along a Hilbert curve, keeping consecutive tiles adjacent
+}
+
+// Splits a `width` x `height` image into tiles of at most `size` x `size` pixels
+pub fn tiles(width: usize, height: usize, size: usize, order: TileOrder) -> Vec<Tile> {
+    let size = size.max(1);
+    let (nx, ny) = (width.div_ceil(size), height.div_ceil(size));
+    let tile_at = |(tx, ty): (usize, usize)| Tile {
+        x: tx * size,
+        y: ty * size,
+        width: size.min(width - tx * size),
+        height: size.min(height - ty * size),
+    };
+
+    let mut coords: Vec<(usize, usize)> = (0..ny)
+        .flat_map(|ty| (0..nx).map(move |tx| (tx, ty)))
+        .collect();
+    match order {
+        TileOrder::Scanline => {}
+        TileOrder::Spiral => coords = spiral(nx, ny),
+        TileOrder::Hilbert => {
+            let n = nx.max(ny).next_power_of_two();
+            coords.sort_by_key(|&(tx, ty)| hilbert_index(n, tx, ty));
+        }
+    }
+    coords.into_iter().map(tile_at).collect()
+}
+
+// Square spiral over an nx x ny grid, starting at the centre cell
+fn spiral(nx: usize, ny: usize) -> Vec<(usize, usize)> {
+    const DIRECTIONS: [(i64, i64); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+    let total = nx * ny;
+    let mut coords = Vec::with_capacity(total);
+    let (mut x, mut y) = ((nx / 2) as i64, (ny / 2) as i64);
+    let push = |x: i64, y: i64, coords: &mut Vec<(usize, usize)>| {
+        if (0..nx as i64).contains(&x) && (0..ny as i64).contains(&y) {
+            coords.push((x as usize, y as usize));
+        }
+    };
+    push(x, y, &mut coords);
+
+    // Legs grow by one every second turn: 1, 1, 2, 2, 3, 3, ...
+    let mut leg = 1;
+    let mut direction = 0;
+    while coords.len() < total {
+        for _ in 0..2 {
+            let (dx, dy) = DIRECTIONS[direction];
+            for _ in 0..leg {
+                x += dx;
+                y += dy;
+                push(x, y, &mut coords);
+            }
+            direction = (direction + 1) % 4;
+        }
+        leg += 1;
+    }
+    coords
+}
+
+// Distance of (x, y) along the Hilbert curve filling an n x n grid, n a power of two
+fn hilbert_index(n: usize, mut x: usize, mut y: usize) -> usize {
+    let mut d = 0;
+    let mut s = n / 2;
+    while s > 0 {
+        let rx = (x & s > 0) as usize;
+        let ry = (y & s > 0) as usize;
+        d += s * s * ((3 * rx) ^ ry);
+
+        // Rotate the quadrant so the sub-curve is in standard orientation
+        if ry == 0 {
+            if rx == 1 {
+                x = n - 1 - x;
+                y = n - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    d
+}
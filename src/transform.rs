@@ -0,0 +1,352 @@
+use crate::aabb::{Aabb, EMPTY_AABB, UNIVERSE_AABB};
+use crate::degrees_to_radians;
+use crate::mat4::Mat4;
+use crate::ray::Ray;
+use crate::vec3::{Point3, Vec3};
+
+// Affine object-to-world transform kept together with its inverse
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Transform {
+    matrix: Mat4,
+    inverse: Mat4,
+}
+
+impl Transform {
+    pub fn identity() -> Self {
+        Self::default()
+    }
+
+    // None if the matrix is not invertible
+    pub fn from_matrix(matrix: Mat4) -> Option<Self> {
+        let inverse = matrix.inverse()?;
+        Some(Self { matrix, inverse })
+    }
+
+    pub fn matrix(&self) -> Mat4 {
+        self.matrix
+    }
+
+    pub fn inverse_matrix(&self) -> Mat4 {
+        self.inverse
+    }
+
+    // Apply `next` after this transform
+    pub fn then(&self, next: Transform) -> Self {
+        Self {
+            matrix: next.matrix * self.matrix,
+            inverse: self.inverse * next.inverse,
+        }
+    }
+
+    pub fn translate(&self, offset: Vec3) -> Self {
+        self.then(Self {
+            matrix: Mat4::translation(offset),
+            inverse: Mat4::translation(-offset),
+        })
+    }
+
+    // Panics if a factor is zero, which flattens the object and can't be undone
+    pub fn scale(&self, factors: Vec3) -> Self {
+        assert!(
+            factors.x != 0.0 && factors.y != 0.0 && factors.z != 0.0,
+            "scale factors must be non-zero"
+        );
+        self.then(Self {
+            matrix: Mat4::scaling(factors),
+            inverse: Mat4::scaling(Vec3::new(1.0 / factors.x, 1.0 / factors.y, 1.0 / factors.z)),
+        })
+    }
+
+    pub fn scale_uniform(&self, factor: f64) -> Self {
+        self.scale(Vec3::new(factor, factor, factor))
+    }
+
+    pub fn rotate(&self, axis: Vec3, degrees: f64) -> Self {
+        // Rotations are orthogonal, so the inverse is the transpose
+        let rotation = Mat4::rotation(axis, degrees);
+        self.then(Self {
+            matrix: rotation,
+            inverse: rotation.transpose(),
+        })
+    }
+
+    pub fn rotate_x(&self, degrees: f64) -> Self {
+        self.rotate(Vec3::new(1.0, 0.0, 0.0), degrees)
+    }
+
+    pub fn rotate_y(&self, degrees: f64) -> Self {
+        self.rotate(Vec3::new(0.0, 1.0, 0.0), degrees)
+    }
+
+    pub fn rotate_z(&self, degrees: f64) -> Self {
+        self.rotate(Vec3::new(0.0, 0.0, 1.0), degrees)
+    }
+
+    pub fn point_to_world(&self, p: Point3) -> Point3 {
+        self.matrix.transform_point(p)
+    }
+
+    pub fn vector_to_world(&self, v: Vec3) -> Vec3 {
+        self.matrix.transform_vector(v)
+    }
+
+    // Normals transform by the inverse transpose to stay perpendicular to the surface
+    pub fn normal_to_world(&self, n: Vec3) -> Vec3 {
+        self.inverse.transpose().transform_vector(n).unit()
+    }
+
+    // The direction is not normalized, so hit distances t are the same in both spaces
+    pub fn ray_to_object(&self, ray: &Ray) -> Ray {
+        Ray::with_time(
+            self.inverse.transform_point(ray.origin),
+            self.inverse.transform_vector(ray.direction),
+            ray.time,
+        )
+    }
+
+    pub fn bbox_to_world(&self, bbox: Aabb) -> Aabb {
+        if bbox.is_empty() {
+            return bbox;
+        }
+        if !bbox.is_finite() {
+            return UNIVERSE_AABB;
+        }
+
+        let mut result = EMPTY_AABB;
+        for i in 0..2 {
+            for j in 0..2 {
+                for k in 0..2 {
+                    let corner = Point3::new(
+                        if i == 0 { bbox.x.min } else { bbox.x.max },
+                        if j == 0 { bbox.y.min } else { bbox.y.max },
+                        if k == 0 { bbox.z.min } else { bbox.z.max },
+                    );
+                    let p = self.point_to_world(corner);
+                    result = Aabb::surrounding(result, Aabb::from_points(p, p));
+                }
+            }
+        }
+        result
+    }
+}
+
+// Pose of an animated object at a point in time. Applied as scale, then rotation, then
+// translation.
+#[derive(Debug, Clone, Copy)]
+pub struct Keyframe {
+    pub time: f64,
+    pub translation: Vec3,
+    pub rotation_axis: Vec3,
+    pub rotation_degrees: f64,
+    pub scale: Vec3,
+}
+
+impl Keyframe {
+    pub fn new(time: f64, translation: Vec3) -> Self {
+        Self {
+            time,
+            translation,
+            rotation_axis: Vec3::new(0.0, 1.0, 0.0),
+            rotation_degrees: 0.0,
+            scale: Vec3::new(1.0, 1.0, 1.0),
+        }
+    }
+
+    pub fn with_rotation(mut self, axis: Vec3, degrees: f64) -> Self {
+        self.rotation_axis = axis;
+        self.rotation_degrees = degrees;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vec3) -> Self {
+        self.scale = scale;
+        self
+    }
+}
+
+// Transform interpolated between keyframes: linearly for translation and scale, spherically for
+// rotation. Times outside the keyframes hold the first or last pose.
+#[derive(Debug, Clone)]
+pub struct AnimatedTransform {
+    keyframes: Vec<Keyframe>,
+}
+
+// Steps per keyframe interval used to bound the swept volume
+const BBOX_STEPS: usize = 16;
+
+impl AnimatedTransform {
+    pub fn new(mut keyframes: Vec<Keyframe>) -> Self {
+        assert!(
+            !keyframes.is_empty(),
+            "animation needs at least one keyframe"
+        );
+        assert!(
+            keyframes
+                .iter()
+                .all(|k| k.scale.x != 0.0 && k.scale.y != 0.0 && k.scale.z != 0.0),
+            "keyframe scale must be non-zero"
+        );
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        // Scales of opposite sign would interpolate through zero
+        assert!(
+            keyframes.windows(2).all(|pair| {
+                let (a, b) = (pair[0].scale, pair[1].scale);
+                a.x * b.x > 0.0 && a.y * b.y > 0.0 && a.z * b.z > 0.0
+            }),
+            "keyframe scale must not change sign between keyframes"
+        );
+        Self { keyframes }
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    pub fn at(&self, time: f64) -> Transform {
+        let first = &self.keyframes[0];
+        let last = &self.keyframes[self.keyframes.len() - 1];
+        if time <= first.time {
+            return Self::pose(first.translation, Quat::from_keyframe(first), first.scale);
+        }
+        if time >= last.time {
+            return Self::pose(last.translation, Quat::from_keyframe(last), last.scale);
+        }
+
+        let next = self.keyframes.partition_point(|k| k.time <= time);
+        let (a, b) = (&self.keyframes[next - 1], &self.keyframes[next]);
+        let s = (time - a.time) / (b.time - a.time);
+
+        Self::pose(
+            (1.0 - s) * a.translation + s * b.translation,
+            Quat::from_keyframe(a).slerp(Quat::from_keyframe(b), s),
+            (1.0 - s) * a.scale + s * b.scale,
+        )
+    }
+
+    // Bounds of the volume swept by bbox over the whole animation
+    pub fn bbox_to_world(&self, bbox: Aabb) -> Aabb {
+        let mut result = self.at(self.keyframes[0].time).bbox_to_world(bbox);
+        for pair in self.keyframes.windows(2) {
+            for step in 1..=BBOX_STEPS {
+                let time =
+                    pair[0].time + (pair[1].time - pair[0].time) * step as f64 / BBOX_STEPS as f64;
+                result = Aabb::surrounding(result, self.at(time).bbox_to_world(bbox));
+            }
+        }
+        if !result.is_finite() || self.keyframes.len() == 1 {
+            return result;
+        }
+
+        // Rotations can bulge slightly outside the sampled poses
+        let padding = 0.01 * result.extent().length();
+        Aabb::new(
+            result.x.expand(padding),
+            result.y.expand(padding),
+            result.z.expand(padding),
+        )
+    }
+
+    // Called for every ray, so the inverse is put together from the inverted parts rather than
+    // by inverting the matrix
+    fn pose(translation: Vec3, rotation: Quat, scale: Vec3) -> Transform {
+        let inverse_scale = Vec3::new(1.0 / scale.x, 1.0 / scale.y, 1.0 / scale.z);
+        Transform {
+            matrix: Mat4::translation(translation) * rotation.to_matrix() * Mat4::scaling(scale),
+            inverse: Mat4::scaling(inverse_scale)
+                * rotation.conjugate().to_matrix()
+                * Mat4::translation(-translation),
+        }
+    }
+}
+
+// Unit quaternion, only used to interpolate rotations
+#[derive(Debug, Clone, Copy)]
+struct Quat {
+    w: f64,
+    v: Vec3,
+}
+
+impl Quat {
+    fn from_keyframe(keyframe: &Keyframe) -> Self {
+        let half = 0.5 * degrees_to_radians(keyframe.rotation_degrees);
+        let axis = if keyframe.rotation_axis.near_zero() {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            keyframe.rotation_axis.unit()
+        };
+        Self {
+            w: half.cos(),
+            v: half.sin() * axis,
+        }
+    }
+
+    fn dot(&self, other: Quat) -> f64 {
+        self.w * other.w + self.v.dot(other.v)
+    }
+
+    fn slerp(&self, other: Quat, s: f64) -> Self {
+        // Take the shorter arc
+        let mut cos_theta = self.dot(other);
+        let other = if cos_theta < 0.0 {
+            cos_theta = -cos_theta;
+            Quat {
+                w: -other.w,
+                v: -other.v,
+            }
+        } else {
+            other
+        };
+
+        let (a, b) = if cos_theta > 0.9995 {
+            (1.0 - s, s)
+        } else {
+            let theta = cos_theta.acos();
+            let sin_theta = theta.sin();
+            (
+                ((1.0 - s) * theta).sin() / sin_theta,
+                (s * theta).sin() / sin_theta,
+            )
+        };
+
+        let w = a * self.w + b * other.w;
+        let v = a * self.v + b * other.v;
+        let norm = (w * w + v.length_squared()).sqrt();
+        Quat {
+            w: w / norm,
+            v: v / norm,
+        }
+    }
+
+    // The inverse rotation, for a unit quaternion
+    fn conjugate(self) -> Self {
+        Quat {
+            w: self.w,
+            v: -self.v,
+        }
+    }
+
+    fn to_matrix(self) -> Mat4 {
+        let (w, Vec3 { x, y, z }) = (self.w, self.v);
+        Mat4::new([
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y),
+                0.0,
+            ],
+            [
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x),
+                0.0,
+            ],
+            [
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y),
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+}
@@ -1,6 +1,6 @@
-use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::ops::{Add, AddAssign, Div, DivAssign, Index, Mul, MulAssign, Neg, Sub, SubAssign};
 
-use crate::{random_f64, random_f64_in_range};
+use crate::{PI, random_f64, random_f64_in_range};
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Vec3 {
@@ -14,7 +14,7 @@ pub type Point3 = Vec3;
 // Utility functions
 pub fn random_unit_vector() -> Vec3 {
     loop {
-        let p = Vec3::random();
+        let p = Vec3::random_range(-1.0, 1.0);
         let lensq = p.length_squared();
         if 1e-160 < lensq && lensq <= 1.0 {
             return p / lensq.sqrt();
@@ -38,12 +38,36 @@ pub fn random_in_unit_disk() -> Vec3 {
 pub fn random_vector_on_hemisphere(normal: Vec3) -> Vec3 {
     let on_unit_sphere = random_unit_vector();
     if on_unit_sphere.dot(normal) > 0.0 {
-        return on_unit_sphere;
+        on_unit_sphere
     } else {
-        return -on_unit_sphere;
+        -on_unit_sphere
     }
 }
 
+// Uniform direction from a point in the unit square
+pub fn sample_unit_vector(u: f64, v: f64) -> Vec3 {
+    let z = 1.0 - 2.0 * u;
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    let phi = 2.0 * PI * v;
+    Vec3::new(r * phi.cos(), r * phi.sin(), z)
+}
+
+// Uniform point in the unit disk from a point in the unit square, using Shirley and Chiu's
+// concentric mapping so that strata in the square stay compact on the disk
+pub fn sample_unit_disk(u: f64, v: f64) -> Vec3 {
+    let (a, b) = (2.0 * u - 1.0, 2.0 * v - 1.0);
+    if a == 0.0 && b == 0.0 {
+        return Vec3::zero();
+    }
+
+    let (r, theta) = if a.abs() > b.abs() {
+        (a, PI / 4.0 * (b / a))
+    } else {
+        (b, PI / 2.0 - PI / 4.0 * (a / b))
+    };
+    Vec3::new(r * theta.cos(), r * theta.sin(), 0.0)
+}
+
 pub fn reflect(v: Vec3, n: Vec3) -> Vec3 {
     v - 2.0 * v.dot(n) * n
 }
@@ -92,6 +116,33 @@ impl Vec3 {
         *self / self.length()
     }
 
+    pub fn abs(&self) -> Self {
+        Self {
+            x: self.x.abs(),
+            y: self.y.abs(),
+            z: self.z.abs(),
+        }
+    }
+
+    // Index of the largest component
+    pub fn max_dimension(&self) -> usize {
+        if self.x > self.y {
+            if self.x > self.z { 0 } else { 2 }
+        } else if self.y > self.z {
+            1
+        } else {
+            2
+        }
+    }
+
+    pub fn permute(&self, x: usize, y: usize, z: usize) -> Self {
+        Self {
+            x: self[x],
+            y: self[y],
+            z: self[z],
+        }
+    }
+
     pub fn near_zero(&self) -> bool {
         let s = 1e-8;
         self.x.abs() < s && self.y.abs() < s && self.z.abs() < s
@@ -114,6 +165,19 @@ impl Vec3 {
     }
 }
 
+impl Index<usize> for Vec3 {
+    type Output = f64;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Vec3 index out of range: {}", index),
+        }
+    }
+}
+
 impl Add for Vec3 {
     type Output = Self;
 